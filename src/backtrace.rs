@@ -0,0 +1,115 @@
+//! Frame-pointer based stack walking and symbol lookup for panic backtraces.
+//!
+//! Every crate is built with `-Cforce-frame-pointers=yes`, so on RISC-V each
+//! frame stores the return address at `fp - 8` and the caller's frame pointer
+//! at `fp - 16`. Symbol names come from the `.usersym` section, which is
+//! reserved here and filled in after linking by `symtab.py`. If that step was
+//! skipped, only raw addresses are printed.
+
+use core::arch::asm;
+use core::convert::TryInto;
+
+/// Upper bound on printed frames, in case the chain is corrupted.
+pub const MAX_FRAMES: usize = 32;
+
+const SYMTAB_SIZE: usize = 8192;
+const SYMTAB_MAGIC: &[u8; 4] = b"USYM";
+
+/// Layout (little endian): magic, `count: u32`, then `count` entries of
+/// `addr: u64, name_off: u32, name_len: u32` sorted by `addr`, then the names.
+#[used]
+#[link_section = ".usersym"]
+static SYMTAB: [u8; SYMTAB_SIZE] = [0; SYMTAB_SIZE];
+
+fn text_range() -> (usize, usize) {
+    extern "C" {
+        fn stext();
+        fn etext();
+    }
+    (stext as usize, etext as usize)
+}
+
+/// Fill `frames` with the return addresses of the active call chain, starting
+/// with the one into the caller of `backtrace`. Returns the number written.
+#[inline(never)]
+pub fn backtrace(frames: &mut [usize]) -> usize {
+    let (stext, etext) = text_range();
+    let mut fp: usize;
+    unsafe {
+        asm!("mv {}, s0", out(reg) fp);
+    }
+    let mut n = 0;
+    while n < frames.len() && fp != 0 && fp % 8 == 0 {
+        let ra = unsafe { *((fp - 8) as *const usize) };
+        let prev_fp = unsafe { *((fp - 16) as *const usize) };
+        if ra < stext || ra >= etext {
+            break;
+        }
+        frames[n] = ra;
+        n += 1;
+        // the stack grows downwards, so callers always live at higher addresses
+        if prev_fp <= fp {
+            break;
+        }
+        fp = prev_fp;
+    }
+    n
+}
+
+fn read_u32(table: &[u8], off: usize) -> usize {
+    u32::from_le_bytes(table[off..off + 4].try_into().unwrap()) as usize
+}
+
+fn read_u64(table: &[u8], off: usize) -> usize {
+    u64::from_le_bytes(table[off..off + 8].try_into().unwrap()) as usize
+}
+
+/// Find the symbol containing `addr`, returning its name and the offset of
+/// `addr` into it. `None` if no symbol table was embedded.
+pub fn resolve(addr: usize) -> Option<(&'static str, usize)> {
+    // SYMTAB is all zeros at compile time, keep the compiler from folding reads
+    let table: &'static [u8] =
+        unsafe { core::slice::from_raw_parts(core::hint::black_box(SYMTAB.as_ptr()), SYMTAB_SIZE) };
+    if &table[..4] != SYMTAB_MAGIC {
+        return None;
+    }
+    let count = read_u32(table, 4);
+    let entry = |i: usize| 8 + i * 16;
+    // index of the last symbol starting at or below addr
+    let (mut lo, mut hi) = (0, count);
+    while lo < hi {
+        let mid = (lo + hi) / 2;
+        if read_u64(table, entry(mid)) <= addr {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+    if lo == 0 {
+        return None;
+    }
+    let off = entry(lo - 1);
+    let start = read_u64(table, off);
+    let name_off = read_u32(table, off + 8);
+    let name_len = read_u32(table, off + 12);
+    let name = table.get(name_off..name_off + name_len)?;
+    Some((core::str::from_utf8(name).ok()?, addr - start))
+}
+
+/// Print the current call chain next to panic messages, one frame per line.
+#[inline(never)]
+pub fn print_backtrace() {
+    let mut frames = [0usize; MAX_FRAMES];
+    let n = backtrace(&mut frames);
+    panic_println!("Backtrace:");
+    for (i, &ra) in frames[..n].iter().enumerate() {
+        match resolve(ra) {
+            Some((name, off)) => {
+                panic_println!("  #{} {:#x} {}+{:#x}", i, ra, name, off);
+            }
+            None => {
+                panic_println!("  #{} {:#x}", i, ra);
+            }
+        }
+    }
+}
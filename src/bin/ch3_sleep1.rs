@@ -4,13 +4,14 @@
 #[macro_use]
 extern crate user_lib;
 
-use user_lib::{get_time, sleep};
+use user_lib::time::Duration;
+use user_lib::{get_time, sleep_yield};
 
 #[no_mangle]
 pub fn main() -> i32 {
     let start = get_time();
     println!("current time_msec = {}", start);
-    sleep(100);
+    sleep_yield(Duration::from_millis(100));
     let end = get_time();
     println!(
         "time_msec = {} after sleeping 100 ticks, delta = {}ms!",
@@ -3,8 +3,9 @@
 
 extern crate user_lib;
 
+use user_lib::time::Duration;
 use user_lib::{
-    count_syscall, get_time, println, sleep, trace_read, trace_write, SYSCALL_EXIT,
+    count_syscall, get_time, println, sleep_yield, trace_read, trace_write, SYSCALL_EXIT,
     SYSCALL_GETTIMEOFDAY, SYSCALL_TRACE, SYSCALL_WRITE, SYSCALL_YIELD,
 };
 
@@ -16,7 +17,7 @@ pub fn write_const(var: &u8, new_val: u8) {
 pub fn main() -> usize {
     let t1 = get_time() as usize;
     get_time();
-    sleep(500);
+    sleep_yield(Duration::from_millis(500));
     let t2 = get_time() as usize;
     let t3 = get_time() as usize;
     assert!(3 <= count_syscall(SYSCALL_GETTIMEOFDAY));
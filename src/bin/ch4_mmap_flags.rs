@@ -0,0 +1,101 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{mmap_with, munmap, MapFlags, ProtFlags};
+
+/*
+理想结果：匿名映射可读写且初始为 0，非法参数返回 -1，
+最终输出 Test mmap_flags OK!
+*/
+
+const PAGE_SIZE: usize = 4096;
+
+fn check_zero_and_fill(start: usize, len: usize) {
+    for i in start..(start + len) {
+        let addr = i as *mut u8;
+        unsafe {
+            assert_eq!(*addr, 0);
+            *addr = i as u8;
+        }
+    }
+    for i in start..(start + len) {
+        let addr = i as *const u8;
+        unsafe {
+            assert_eq!(*addr, i as u8);
+        }
+    }
+}
+
+#[no_mangle]
+fn main() -> i32 {
+    let rw = ProtFlags::READ | ProtFlags::WRITE;
+    let anon = MapFlags::PRIVATE | MapFlags::ANONYMOUS;
+
+    // 由内核选择地址
+    let addr = mmap_with(0, PAGE_SIZE * 2, rw, anon, None, 0);
+    assert!(addr > 0);
+    assert_eq!(addr as usize % PAGE_SIZE, 0);
+    check_zero_and_fill(addr as usize, PAGE_SIZE * 2);
+
+    // 固定地址映射
+    let start: usize = 0x10000000;
+    assert_eq!(
+        mmap_with(start, PAGE_SIZE, rw, anon | MapFlags::FIXED, None, 0),
+        start as isize
+    );
+    check_zero_and_fill(start, PAGE_SIZE);
+    // 已有映射时 FIXED_NOREPLACE 失败
+    assert!(
+        mmap_with(
+            start,
+            PAGE_SIZE,
+            rw,
+            anon | MapFlags::FIXED_NOREPLACE,
+            None,
+            0
+        ) < 0
+    );
+    // FIXED 替换原映射，内容重新清零
+    assert_eq!(
+        mmap_with(start, PAGE_SIZE, rw, anon | MapFlags::FIXED, None, 0),
+        start as isize
+    );
+    check_zero_and_fill(start, PAGE_SIZE);
+    assert_eq!(munmap(start, PAGE_SIZE), 0);
+
+    // 非法参数
+    let fixed = anon | MapFlags::FIXED;
+    // SHARED 与 PRIVATE 必须且只能指定一个
+    assert!(
+        mmap_with(
+            start,
+            PAGE_SIZE,
+            rw,
+            MapFlags::ANONYMOUS | MapFlags::FIXED,
+            None,
+            0
+        ) < 0
+    );
+    assert!(mmap_with(start, PAGE_SIZE, rw, fixed | MapFlags::SHARED, None, 0) < 0);
+    // 长度为 0、固定地址未对齐
+    assert!(mmap_with(start, 0, rw, fixed, None, 0) < 0);
+    assert!(mmap_with(start + 1, PAGE_SIZE, rw, fixed, None, 0) < 0);
+    // 非匿名映射必须给出文件
+    assert!(
+        mmap_with(
+            start,
+            PAGE_SIZE,
+            rw,
+            MapFlags::PRIVATE | MapFlags::FIXED,
+            None,
+            0
+        ) < 0
+    );
+
+    assert_eq!(munmap(addr as usize, PAGE_SIZE * 2), 0);
+    println!("Test mmap_flags OK!");
+    0
+}
@@ -0,0 +1,30 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{mmap, mprotect, ProtFlags};
+
+/*
+理想结果：降级为只读后仍可读取原有数据，随后的写入触发访存异常，程序被杀死。
+不输出 error 就算过。
+*/
+
+#[no_mangle]
+fn main() -> i32 {
+    let start: usize = 0x10000000;
+    let len: usize = 4096;
+    assert_eq!(0, mmap(start, len, 3));
+    let addr: *mut u8 = start as *mut u8;
+    unsafe {
+        *addr = 42;
+    }
+    assert_eq!(mprotect(start, len, ProtFlags::READ), 0);
+    unsafe {
+        assert_eq!(core::ptr::read_volatile(addr), 42);
+        core::ptr::write_volatile(addr, 0);
+    }
+    println!("Should cause error, Test mprotect0 fail!");
+    0
+}
@@ -0,0 +1,38 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{mmap, mprotect, ProtFlags};
+
+/*
+理想结果：把代码复制到映射的页面中，改为可执行后调用，输出 Test mprotect1 OK!
+*/
+
+/// li a0, 42; ret
+const CODE: [u32; 2] = [0x02a0_0513, 0x0000_8067];
+
+#[no_mangle]
+fn main() -> i32 {
+    let start: usize = 0x10000000;
+    let len: usize = 4096;
+    assert_eq!(0, mmap(start, len, 3));
+    let page = start as *mut u32;
+    for (i, &inst) in CODE.iter().enumerate() {
+        unsafe {
+            page.add(i).write_volatile(inst);
+        }
+    }
+    assert_eq!(mprotect(start, len, ProtFlags::READ | ProtFlags::EXEC), 0);
+    let ret = unsafe {
+        core::arch::asm!("fence.i");
+        let f: extern "C" fn() -> usize = core::mem::transmute(start);
+        f()
+    };
+    assert_eq!(ret, 42);
+    // 代码页仍然可读
+    assert_eq!(unsafe { page.read_volatile() }, CODE[0]);
+    println!("Test mprotect1 OK!");
+    0
+}
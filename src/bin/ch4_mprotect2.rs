@@ -0,0 +1,43 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{mmap, mprotect, munmap, sys_mprotect, ProtFlags};
+
+/*
+理想结果：对于错误的 mprotect 返回负数，最终输出 Test mprotect2 OK!
+*/
+
+#[no_mangle]
+fn main() -> i32 {
+    let start: usize = 0x10000000;
+    let len: usize = 4096;
+    let rw = ProtFlags::READ | ProtFlags::WRITE;
+    assert_eq!(0, mmap(start, len * 2, 3));
+    // 起始地址未对齐
+    assert!(mprotect(start + 1, len, ProtFlags::READ) < 0);
+    // 范围包含未映射的页
+    assert!(mprotect(start - len, len * 2, ProtFlags::READ) < 0);
+    assert!(mprotect(start + len, len * 2, ProtFlags::READ) < 0);
+    assert!(mprotect(start + len * 4, len, ProtFlags::READ) < 0);
+    // 非法的权限位
+    assert!(sys_mprotect(start, len, 8) < 0);
+    assert!(sys_mprotect(start, len, 3 | 8) < 0);
+    // 只修改一部分页面，长度不足一页时按页向上取整
+    assert_eq!(mprotect(start + len, 1, ProtFlags::READ), 0);
+    assert_eq!(mprotect(start + len, len, rw), 0);
+    for i in start..(start + len * 2) {
+        let addr: *mut u8 = i as *mut u8;
+        unsafe {
+            *addr = i as u8;
+        }
+    }
+    // 解除映射后不能再修改权限
+    assert_eq!(munmap(start, len), 0);
+    assert!(mprotect(start, len, rw) < 0);
+    assert_eq!(mprotect(start + len, len, rw), 0);
+    println!("Test mprotect2 OK!");
+    0
+}
@@ -0,0 +1,93 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{exit, fork, mmap_with, munmap, sbrk, waitpid, MapFlags, ProtFlags};
+
+/*
+理想结果：fork 之后父子进程各自修改 sbrk 堆和 mmap 区域，
+双方看到的内容逐字节符合各自的写入，互不影响。最终输出 Test cow_isolation OK!
+*/
+
+const PAGE_SIZE: usize = 4096;
+const HEAP_LEN: usize = 64 * PAGE_SIZE;
+const MMAP_LEN: usize = 16 * PAGE_SIZE;
+
+const ORIGINAL: u8 = 1;
+const CHILD: u8 = 2;
+const PARENT: u8 = 3;
+
+fn pattern(i: usize, seed: u8) -> u8 {
+    (i.wrapping_mul(31) ^ (i / PAGE_SIZE) ^ (seed as usize * 0x55)) as u8
+}
+
+/// 用 `seed` 生成的内容填充 `mem` 中满足 `pick(page)` 的页
+fn fill(mem: &mut [u8], seed: u8, pick: fn(usize) -> bool) {
+    for (i, b) in mem.iter_mut().enumerate() {
+        if pick(i / PAGE_SIZE) {
+            *b = pattern(i, seed);
+        }
+    }
+}
+
+/// 偶数页应为 `even` 生成的内容，奇数页应为 `odd` 生成的内容
+fn verify(mem: &[u8], even: u8, odd: u8, what: &str) {
+    for (i, &b) in mem.iter().enumerate() {
+        let seed = if (i / PAGE_SIZE) % 2 == 0 { even } else { odd };
+        assert_eq!(b, pattern(i, seed), "{} differs at offset {:#x}", what, i);
+    }
+}
+
+fn all(_: usize) -> bool {
+    true
+}
+
+fn even(page: usize) -> bool {
+    page % 2 == 0
+}
+
+fn odd(page: usize) -> bool {
+    page % 2 == 1
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let heap_start = sbrk(HEAP_LEN as i32);
+    assert!(heap_start > 0);
+    let heap = unsafe { core::slice::from_raw_parts_mut(heap_start as *mut u8, HEAP_LEN) };
+    let rw = ProtFlags::READ | ProtFlags::WRITE;
+    let anon = MapFlags::PRIVATE | MapFlags::ANONYMOUS;
+    let map_start = mmap_with(0, MMAP_LEN, rw, anon, None, 0);
+    assert!(map_start > 0);
+    let map = unsafe { core::slice::from_raw_parts_mut(map_start as *mut u8, MMAP_LEN) };
+    fill(heap, ORIGINAL, all);
+    fill(map, ORIGINAL, all);
+
+    let pid = fork();
+    if pid == 0 {
+        // 不管父进程是否已经修改，子进程看到的都是 fork 时的内容
+        verify(heap, ORIGINAL, ORIGINAL, "child heap before write");
+        verify(map, ORIGINAL, ORIGINAL, "child mmap before write");
+        fill(heap, CHILD, even);
+        fill(map, CHILD, odd);
+        verify(heap, CHILD, ORIGINAL, "child heap after write");
+        verify(map, ORIGINAL, CHILD, "child mmap after write");
+        exit(0);
+    }
+    assert!(pid > 0);
+    fill(heap, PARENT, odd);
+    fill(map, PARENT, even);
+    let mut exit_code = 0;
+    assert_eq!(waitpid(pid as usize, &mut exit_code), pid);
+    assert_eq!(exit_code, 0);
+    // 子进程的写入对父进程不可见
+    verify(heap, ORIGINAL, PARENT, "parent heap");
+    verify(map, PARENT, ORIGINAL, "parent mmap");
+
+    assert_eq!(munmap(map_start as usize, MMAP_LEN), 0);
+    assert_eq!(sbrk(-(HEAP_LEN as i32)), heap_start + HEAP_LEN as isize);
+    println!("Test cow_isolation OK!");
+    0
+}
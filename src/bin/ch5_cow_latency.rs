@@ -0,0 +1,82 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::time::Instant;
+use user_lib::{exit, fork, sbrk, waitpid};
+
+/*
+理想结果：随着堆增大，fork 的耗时只缓慢增长；
+子进程第一次写入每一页（触发复制）的总耗时应超过 fork 本身的耗时。
+输出各堆大小下的耗时，最终输出 Test cow_latency OK!
+*/
+
+const PAGE_SIZE: usize = 4096;
+const HEAP_PAGES: [usize; 5] = [0, 16, 64, 256, 512];
+const ROUNDS: usize = 3;
+
+/// 返回 (fork 耗时, 子进程写入每页耗时)，单位 us，取多轮的最小值，
+/// 以排除 fork 后先调度子进程等干扰
+fn measure(heap: &mut [u8]) -> (u128, u128) {
+    let mut best_fork = u128::MAX;
+    let mut best_touch = u128::MAX;
+    for _ in 0..ROUNDS {
+        let start = Instant::now();
+        let pid = fork();
+        if pid == 0 {
+            let touch_start = Instant::now();
+            for page in heap.chunks_mut(PAGE_SIZE) {
+                unsafe {
+                    core::ptr::write_volatile(&mut page[0], 0xaa);
+                }
+            }
+            let touched = touch_start.elapsed().as_micros();
+            // 通过退出码把耗时带回父进程
+            exit(touched.min(i32::MAX as u128) as i32);
+        }
+        let forked = start.elapsed().as_micros();
+        let mut exit_code = 0;
+        assert_eq!(waitpid(pid as usize, &mut exit_code), pid);
+        assert!(exit_code >= 0);
+        best_fork = best_fork.min(forked);
+        best_touch = best_touch.min(exit_code as u128);
+    }
+    (best_fork, best_touch)
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let base = sbrk(0);
+    assert!(base > 0);
+    let mut allocated = 0;
+    let mut last = (0, 0);
+    for &pages in HEAP_PAGES.iter() {
+        let grow = (pages - allocated) * PAGE_SIZE;
+        if grow > 0 {
+            assert!(sbrk(grow as i32) > 0);
+        }
+        allocated = pages;
+        let heap = unsafe { core::slice::from_raw_parts_mut(base as *mut u8, pages * PAGE_SIZE) };
+        // 先确保所有页都已真正分配
+        for page in heap.chunks_mut(PAGE_SIZE) {
+            page[0] = 0x55;
+        }
+        last = measure(heap);
+        println!(
+            "heap {:>4} pages: fork {:>6}us, child first write {:>6}us",
+            pages, last.0, last.1
+        );
+    }
+    // 如果 fork 时就复制了所有页，子进程写入几乎不花时间
+    assert!(
+        last.0 < last.1,
+        "fork ({}us) slower than copying on write ({}us), is COW in effect?",
+        last.0,
+        last.1
+    );
+    sbrk(-((allocated * PAGE_SIZE) as i32));
+    println!("Test cow_latency OK!");
+    0
+}
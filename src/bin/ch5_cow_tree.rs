@@ -0,0 +1,71 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{exit, fork, getpid, waitpid};
+
+/*
+理想结果：与 ch5b_forktree 相同的 fork 树，每一层在 fork 前后都修改属于本层的页面，
+每个进程看到的祖先页面都与 fork 时一致，且看不到兄弟和子孙的修改。
+最终输出 Test cow_tree OK!
+*/
+
+const PAGE_SIZE: usize = 4096;
+const DEPTH: usize = 4;
+
+static mut PAGES: [[u8; PAGE_SIZE]; DEPTH + 1] = [[0; PAGE_SIZE]; DEPTH + 1];
+
+fn page(level: usize) -> &'static mut [u8; PAGE_SIZE] {
+    unsafe { &mut PAGES[level] }
+}
+
+/// 检查第 0..len 层的页面与路径一致，更深的页面保持全 0
+fn verify(path: &[u8]) -> bool {
+    (0..=DEPTH).all(|level| {
+        let expected = path.get(level).copied().unwrap_or(0);
+        page(level).iter().all(|&b| b == expected)
+    })
+}
+
+/// 以 `path` 所在的节点运行子树，返回是否所有检查都通过
+fn fork_tree(path: &[u8]) -> bool {
+    if !verify(path) {
+        println!("pid{}: {:?} sees wrong pages", getpid(), path);
+        return false;
+    }
+    let level = path.len();
+    if level == DEPTH {
+        return true;
+    }
+    let mut next = [0u8; DEPTH];
+    next[..level].copy_from_slice(path);
+    let mut pids = [0isize; 2];
+    for (pid, branch) in pids.iter_mut().zip([b'0', b'1']) {
+        page(level).fill(branch);
+        next[level] = branch;
+        *pid = fork();
+        if *pid == 0 {
+            exit(if fork_tree(&next[..level + 1]) { 0 } else { 1 });
+        }
+        assert!(*pid > 0);
+    }
+    // fork 之后再次修改本层页面，子进程不应看到
+    page(level).fill(b'X');
+    let mut ok = true;
+    for &pid in pids.iter() {
+        let mut exit_code = 0;
+        assert_eq!(waitpid(pid as usize, &mut exit_code), pid);
+        ok &= exit_code == 0;
+    }
+    page(level).fill(0);
+    ok && verify(path)
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    assert!(fork_tree(&[]));
+    println!("Test cow_tree OK!");
+    0
+}
@@ -0,0 +1,52 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::rand::{getrandom, GetRandomFlags, Rng};
+
+/*
+理想结果：getrandom 能填满缓冲区且两次结果不同；
+相同种子的 Rng 产生相同序列，不同种子产生不同序列，below 的结果落在范围内。
+最终输出 Test rand OK!
+*/
+
+const SEED: u64 = 20240502;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let mut a = [0u8; 64];
+    let mut b = [0u8; 64];
+    assert_eq!(getrandom(&mut a, GetRandomFlags::empty()), a.len() as isize);
+    assert_eq!(getrandom(&mut b, GetRandomFlags::empty()), b.len() as isize);
+    assert_ne!(a, b);
+    // 长度为 0 的请求也应成功
+    assert_eq!(getrandom(&mut [], GetRandomFlags::NONBLOCK), 0);
+
+    let mut x = Rng::new(SEED);
+    let mut y = Rng::new(SEED);
+    assert_eq!(x.seed(), SEED);
+    for _ in 0..1000 {
+        assert_eq!(x.next_u64(), y.next_u64());
+    }
+    x.fill_bytes(&mut a);
+    y.fill_bytes(&mut b);
+    assert_eq!(a, b);
+
+    let mut z = Rng::new(SEED + 1);
+    assert!((0..16).any(|_| x.next_u32() != z.next_u32()));
+
+    // --seed=N 覆盖默认种子
+    assert_eq!(Rng::from_args(&["ch5_rand", "--seed=42"], SEED).seed(), 42);
+    assert_eq!(Rng::from_args(&["ch5_rand"], SEED).seed(), SEED);
+
+    let mut counts = [0usize; 6];
+    for _ in 0..6000 {
+        counts[x.below(6) as usize] += 1;
+    }
+    // 每个取值都应出现，且不会严重偏离均值
+    assert!(counts.iter().all(|&c| c > 800 && c < 1200), "{:?}", counts);
+    println!("Test rand OK!");
+    0
+}
@@ -0,0 +1,70 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use core::sync::atomic::{AtomicUsize, Ordering};
+use user_lib::{exit, fork, mmap_with, munmap, waitpid, MapFlags, ProtFlags};
+
+/*
+理想结果：MAP_SHARED 匿名映射在 fork 后由父子进程共享，
+各进程用原子操作累加计数器后总数正确；MAP_PRIVATE 映射互不影响。
+最终输出 Test shm_anon OK!
+*/
+
+const PAGE_SIZE: usize = 4096;
+const CHILDREN: usize = 4;
+const ROUNDS: usize = 1000;
+
+fn map(flags: MapFlags) -> &'static [AtomicUsize] {
+    let rw = ProtFlags::READ | ProtFlags::WRITE;
+    let addr = mmap_with(0, PAGE_SIZE, rw, flags | MapFlags::ANONYMOUS, None, 0);
+    assert!(addr > 0);
+    unsafe {
+        core::slice::from_raw_parts(
+            addr as *const AtomicUsize,
+            PAGE_SIZE / core::mem::size_of::<AtomicUsize>(),
+        )
+    }
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let shared = map(MapFlags::SHARED);
+    let private = map(MapFlags::PRIVATE);
+    let mut pids = [0isize; CHILDREN];
+    for (i, pid) in pids.iter_mut().enumerate() {
+        *pid = fork();
+        if *pid == 0 {
+            for _ in 0..ROUNDS {
+                shared[0].fetch_add(1, Ordering::SeqCst);
+                private[0].fetch_add(1, Ordering::SeqCst);
+            }
+            // 每个子进程在自己的槽位写入标记，最后一个槽位记录访问过的子进程数
+            shared[i + 1].store(i + 100, Ordering::SeqCst);
+            shared[shared.len() - 1].fetch_add(1, Ordering::SeqCst);
+            assert_eq!(private[0].load(Ordering::SeqCst), ROUNDS);
+            exit(0);
+        }
+        assert!(*pid > 0);
+    }
+    for _ in 0..ROUNDS {
+        shared[0].fetch_add(1, Ordering::SeqCst);
+    }
+    for &pid in pids.iter() {
+        let mut exit_code = 0;
+        assert_eq!(waitpid(pid as usize, &mut exit_code), pid);
+        assert_eq!(exit_code, 0);
+    }
+    assert_eq!(shared[0].load(Ordering::SeqCst), ROUNDS * (CHILDREN + 1));
+    for i in 0..CHILDREN {
+        assert_eq!(shared[i + 1].load(Ordering::SeqCst), i + 100);
+    }
+    assert_eq!(shared[shared.len() - 1].load(Ordering::SeqCst), CHILDREN);
+    assert_eq!(private[0].load(Ordering::SeqCst), 0);
+    assert_eq!(munmap(shared.as_ptr() as usize, PAGE_SIZE), 0);
+    assert_eq!(munmap(private.as_ptr() as usize, PAGE_SIZE), 0);
+    println!("Test shm_anon OK!");
+    0
+}
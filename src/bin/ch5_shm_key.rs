@@ -0,0 +1,107 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use core::sync::atomic::{AtomicUsize, Ordering};
+use user_lib::{
+    exit, fork, shm_attach, shm_detach, shm_get, shm_remove, waitpid, ShmFlags, SHM_PRIVATE,
+};
+
+/*
+理想结果：不同进程通过相同的 key 找到同一个共享内存段并累加计数器，总数正确；
+detach 之后再访问该页面会触发访存异常，子进程被杀死。
+最终输出 Test shm_key OK!
+*/
+
+const PAGE_SIZE: usize = 4096;
+const KEY: usize = 0x5348;
+const CHILDREN: usize = 3;
+const ROUNDS: usize = 1000;
+
+fn counter(addr: isize) -> &'static AtomicUsize {
+    assert!(addr > 0);
+    unsafe { &*(addr as *const AtomicUsize) }
+}
+
+/// 在子进程中运行 `f`，返回子进程的退出码
+fn run_child(f: fn()) -> i32 {
+    let pid = fork();
+    if pid == 0 {
+        f();
+        exit(0);
+    }
+    let mut exit_code = 0;
+    assert_eq!(waitpid(pid as usize, &mut exit_code), pid);
+    exit_code
+}
+
+/// 子进程不继承 attach，而是按 key 重新查找
+fn add_by_key() {
+    let id = shm_get(KEY, PAGE_SIZE, ShmFlags::empty());
+    assert!(id >= 0);
+    let addr = shm_attach(id as usize, 0, ShmFlags::empty());
+    let c = counter(addr);
+    for _ in 0..ROUNDS {
+        c.fetch_add(1, Ordering::SeqCst);
+    }
+    assert_eq!(shm_detach(addr as usize), 0);
+}
+
+fn touch_after_detach() {
+    let id = shm_get(KEY, PAGE_SIZE, ShmFlags::empty());
+    let addr = shm_attach(id as usize, 0, ShmFlags::empty());
+    let c = counter(addr);
+    c.fetch_add(1, Ordering::SeqCst);
+    assert_eq!(shm_detach(addr as usize), 0);
+    c.fetch_add(1, Ordering::SeqCst);
+    println!("Should cause error, Test shm_key fail!");
+}
+
+fn write_read_only() {
+    let id = shm_get(KEY, PAGE_SIZE, ShmFlags::empty());
+    let c = counter(shm_attach(id as usize, 0, ShmFlags::RDONLY));
+    assert!(c.load(Ordering::SeqCst) > 0);
+    c.fetch_add(1, Ordering::SeqCst);
+    println!("Should cause error, Test shm_key fail!");
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    // 不存在且未指定 CREATE 时失败；CREATE | EXCL 对已存在的 key 失败
+    assert_eq!(shm_get(KEY, PAGE_SIZE, ShmFlags::empty()), -1);
+    let id = shm_get(KEY, PAGE_SIZE, ShmFlags::CREATE | ShmFlags::EXCL);
+    assert!(id >= 0);
+    let id = id as usize;
+    assert_eq!(
+        shm_get(KEY, PAGE_SIZE, ShmFlags::CREATE | ShmFlags::EXCL),
+        -1
+    );
+    assert_eq!(shm_get(KEY, PAGE_SIZE, ShmFlags::CREATE), id as isize);
+    // SHM_PRIVATE 总是得到新的段
+    let private_id = shm_get(SHM_PRIVATE, PAGE_SIZE, ShmFlags::CREATE);
+    assert!(private_id >= 0 && private_id as usize != id);
+    assert_eq!(shm_remove(private_id as usize), 0);
+
+    let addr = shm_attach(id, 0, ShmFlags::empty());
+    let c = counter(addr);
+    assert_eq!(c.load(Ordering::SeqCst), 0);
+    for _ in 0..CHILDREN {
+        assert_eq!(run_child(add_by_key), 0);
+    }
+    assert_eq!(c.load(Ordering::SeqCst), ROUNDS * CHILDREN);
+
+    assert_ne!(run_child(touch_after_detach), 0);
+    assert_eq!(c.load(Ordering::SeqCst), ROUNDS * CHILDREN + 1);
+    assert_ne!(run_child(write_read_only), 0);
+    assert_eq!(c.load(Ordering::SeqCst), ROUNDS * CHILDREN + 1);
+
+    // 重复 detach 失败；段被删除后 key 不再可用
+    assert_eq!(shm_detach(addr as usize), 0);
+    assert_eq!(shm_detach(addr as usize), -1);
+    assert_eq!(shm_remove(id), 0);
+    assert_eq!(shm_get(KEY, PAGE_SIZE, ShmFlags::empty()), -1);
+    println!("Test shm_key OK!");
+    0
+}
@@ -0,0 +1,70 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::time::{Duration, Instant};
+use user_lib::{exit, fork, sleep, waitpid};
+
+/*
+理想结果：每次 sleep 都不会提前返回；
+在后台有计算负载时，输出每种时长的平均/最大超时（overshoot）。
+*/
+
+const LENGTHS_MS: [u64; 7] = [1, 2, 5, 10, 20, 50, 100];
+const ROUNDS: u64 = 5;
+const LOADERS: usize = 3;
+
+fn spin_until(deadline: Instant) {
+    let mut acc = 0usize;
+    while Instant::now() < deadline {
+        for _ in 0..100 {
+            acc = acc.wrapping_mul(31).wrapping_add(7);
+        }
+    }
+    core::hint::black_box(acc);
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let total: u64 = LENGTHS_MS.iter().sum::<u64>() * ROUNDS;
+    let deadline = Instant::now() + Duration::from_millis(total * 2 + 500);
+    let mut loaders = [0isize; LOADERS];
+    for pid in loaders.iter_mut() {
+        *pid = fork();
+        if *pid == 0 {
+            spin_until(deadline);
+            exit(0);
+        }
+        assert!(*pid > 0);
+    }
+
+    for &ms in LENGTHS_MS.iter() {
+        let req = Duration::from_millis(ms);
+        let mut sum = Duration::ZERO;
+        let mut max = Duration::ZERO;
+        for _ in 0..ROUNDS {
+            let start = Instant::now();
+            assert_eq!(sleep(req), Ok(Duration::ZERO));
+            let slept = start.elapsed();
+            assert!(slept >= req, "woke up early: {:?} < {:?}", slept, req);
+            let over = slept - req;
+            sum += over;
+            max = max.max(over);
+        }
+        println!(
+            "sleep {}ms: mean overshoot = {}us, max overshoot = {}us",
+            ms,
+            (sum / ROUNDS as u32).as_micros(),
+            max.as_micros()
+        );
+    }
+
+    for &pid in loaders.iter() {
+        let mut exit_code: i32 = 0;
+        assert_eq!(waitpid(pid as usize, &mut exit_code), pid);
+    }
+    println!("Test sleep_accuracy OK!");
+    0
+}
@@ -0,0 +1,85 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use user_lib::{exit, fork, get_time, get_time_us, waitpid, yield_};
+
+/// 正确输出：（无报错信息）
+/// Test time OK!
+
+const ROUNDS: usize = 200;
+const CHILDREN: usize = 4;
+
+/// 多次 yield 切换上下文，检查时间不回退，返回观察到的最小非零间隔
+fn check_monotonic() -> Duration {
+    let mut prev = Instant::now();
+    let mut min_step = Duration::MAX;
+    for i in 0..ROUNDS {
+        if i % 4 == 0 {
+            yield_();
+        }
+        let now = Instant::now();
+        assert!(now >= prev);
+        let step = now - prev;
+        if step > Duration::ZERO && step < min_step {
+            min_step = step;
+        }
+        prev = now;
+    }
+    min_step
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    // 毫秒时间戳不应再按 0xffff 秒回绕
+    let ms = get_time();
+    let us = get_time_us();
+    assert!(ms > 0 && us > 0);
+    assert!(us / 1000 >= ms);
+    assert!(SystemTime::now().duration_since(UNIX_EPOCH).is_ok());
+
+    // 饱和运算
+    let t0 = Instant::now();
+    let t1 = Instant::now();
+    assert_eq!(
+        t0 - t1.checked_add(Duration::from_secs(1)).unwrap(),
+        Duration::ZERO
+    );
+    assert_eq!(
+        t0 + Duration::MAX,
+        t0 + Duration::MAX + Duration::from_secs(1)
+    );
+    assert!(t0.checked_add(Duration::MAX).is_none());
+    assert!(UNIX_EPOCH.duration_since(SystemTime::now()).is_err());
+
+    let min_step = check_monotonic();
+    println!("min observed step = {}ns", min_step.as_nanos());
+
+    // fork 后子进程的时钟不能早于父进程 fork 前的读数
+    let mut pids = [0isize; CHILDREN];
+    let before_fork = Instant::now();
+    for pid in pids.iter_mut() {
+        *pid = fork();
+        if *pid == 0 {
+            assert!(Instant::now() >= before_fork);
+            check_monotonic();
+            exit(0);
+        }
+        assert!(*pid > 0);
+    }
+    let mut last_exit = before_fork;
+    for &pid in pids.iter() {
+        let mut exit_code: i32 = 0;
+        assert_eq!(waitpid(pid as usize, &mut exit_code), pid);
+        assert_eq!(exit_code, 0);
+        let now = Instant::now();
+        assert!(now >= last_exit);
+        last_exit = now;
+    }
+    println!("elapsed = {}us", before_fork.elapsed().as_micros());
+    println!("Test time OK!");
+    0
+}
@@ -0,0 +1,76 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{exit, fork, get_time, wait, waitpid, waitpid_with_flags, yield_, WaitFlags};
+
+/*
+理想结果：父进程阻塞在 waitpid 中时几乎不占用 CPU，
+子进程在相同时间内的计数应接近其独占 CPU 时的计数。
+*/
+
+const MAX_TIME: isize = 1000;
+
+fn spin_delay() {
+    let mut j = true;
+    for _ in 0..10 {
+        j = !j;
+    }
+}
+
+fn count_during() -> usize {
+    let start_time = get_time();
+    let mut acc = 0;
+    loop {
+        spin_delay();
+        acc += 1;
+        if acc % 400 == 0 && get_time() - start_time > MAX_TIME {
+            return acc;
+        }
+    }
+}
+
+/// fork 一个计数子进程，返回其计数相对 `base` 的千分比
+fn child_ratio(base: usize, blocking: bool) -> i32 {
+    let pid = fork();
+    if pid == 0 {
+        let count = count_during();
+        exit((count * 1000 / base) as i32);
+    }
+    assert!(pid > 0);
+    let mut exit_code: i32 = 0;
+    if blocking {
+        assert_eq!(waitpid(pid as usize, &mut exit_code), pid);
+    } else {
+        loop {
+            match waitpid_with_flags(pid, &mut exit_code, WaitFlags::NOHANG) {
+                -2 => {
+                    yield_();
+                }
+                n => {
+                    assert_eq!(n, pid);
+                    break;
+                }
+            }
+        }
+    }
+    exit_code
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let base = count_during();
+    println!("base count = {}", base);
+    let polling = child_ratio(base, false);
+    println!("child ratio while parent polls: {}/1000", polling);
+    let blocking = child_ratio(base, true);
+    println!("child ratio while parent blocks: {}/1000", blocking);
+    assert!(blocking >= 900);
+    // 没有子进程时阻塞等待也应立即返回
+    let mut exit_code: i32 = 0;
+    assert_eq!(wait(&mut exit_code), -1);
+    println!("Test wait_blocking OK!");
+    0
+}
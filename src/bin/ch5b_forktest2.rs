@@ -4,7 +4,8 @@
 #[macro_use]
 extern crate user_lib;
 
-use user_lib::{exit, fork, get_time, getpid, sleep, wait};
+use user_lib::time::Duration;
+use user_lib::{exit, fork, get_time, getpid, sleep_yield, wait};
 
 static NUM: usize = 30;
 
@@ -17,7 +18,7 @@ pub fn main() -> i32 {
             let sleep_length =
                 (current_time as i32 as isize) * (current_time as i32 as isize) % 1000 + 1000;
             println!("pid {} sleep for {} ms", getpid(), sleep_length);
-            sleep(sleep_length as usize);
+            sleep_yield(Duration::from_millis(sleep_length as u64));
             println!("pid {} OK!", getpid());
             exit(0);
         }
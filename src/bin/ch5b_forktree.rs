@@ -4,7 +4,8 @@
 #[macro_use]
 extern crate user_lib;
 
-use user_lib::{exit, fork, getpid, sleep, yield_};
+use user_lib::time::Duration;
+use user_lib::{exit, fork, getpid, sleep_yield, yield_};
 
 const DEPTH: usize = 4;
 
@@ -32,6 +33,6 @@ fn fork_tree(cur: &str) {
 #[no_mangle]
 pub fn main() -> i32 {
     fork_tree("");
-    sleep(3000);
+    sleep_yield(Duration::from_millis(3000));
     0
 }
@@ -5,61 +5,34 @@ extern crate alloc;
 #[macro_use]
 extern crate user_lib;
 
-const LF: u8 = 0x0au8;
-const CR: u8 = 0x0du8;
-const DL: u8 = 0x7fu8;
-const BS: u8 = 0x08u8;
+const HISTORY_SIZE: usize = 16;
 
-use alloc::string::String;
-use user_lib::console::getchar;
-use user_lib::{exec, flush, fork, waitpid};
+use user_lib::readline::Editor;
+use user_lib::{exec, fork, waitpid};
 
 #[no_mangle]
 pub fn main() -> i32 {
     println!("Rust user shell");
-    let mut line: String = String::new();
-    print!(">> ");
-    flush();
-    loop {
-        let c = getchar();
-        match c {
-            LF | CR => {
-                print!("\n");
-                if !line.is_empty() {
-                    line.push('\0');
-                    let pid = fork();
-                    if pid == 0 {
-                        // child process
-                        if exec(line.as_str(), &[core::ptr::null::<u8>()]) == -1 {
-                            println!("Error when executing!");
-                            return -4;
-                        }
-                        unreachable!();
-                    } else {
-                        let mut exit_code: i32 = 0;
-                        let exit_pid = waitpid(pid as usize, &mut exit_code);
-                        assert_eq!(pid, exit_pid);
-                        println!("Shell: Process {} exited with code {}", pid, exit_code);
-                    }
-                    line.clear();
-                }
-                print!(">> ");
-                flush();
-            }
-            BS | DL => {
-                if !line.is_empty() {
-                    print!("{}", BS as char);
-                    print!(" ");
-                    print!("{}", BS as char);
-                    flush();
-                    line.pop();
-                }
-            }
-            _ => {
-                print!("{}", c as char);
-                flush();
-                line.push(c as char);
+    let mut editor = Editor::new(HISTORY_SIZE);
+    while let Some(mut line) = editor.readline(">> ") {
+        if line.is_empty() {
+            continue;
+        }
+        line.push('\0');
+        let pid = fork();
+        if pid == 0 {
+            // child process
+            if exec(line.as_str(), &[core::ptr::null::<u8>()]) == -1 {
+                println!("Error when executing!");
+                return -4;
             }
+            unreachable!();
+        } else {
+            let mut exit_code: i32 = 0;
+            let exit_pid = waitpid(pid as usize, &mut exit_code);
+            assert_eq!(pid, exit_pid);
+            println!("Shell: Process {} exited with code {}", pid, exit_code);
         }
     }
+    0
 }
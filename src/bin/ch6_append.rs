@@ -0,0 +1,102 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{close, exit, fork, open, read, unlink, waitpid, write, OpenFlags};
+
+/*
+理想结果：多个进程各自以 O_APPEND 打开同一文件并交替写入定长记录，
+所有记录都完整出现且恰好出现一次，没有互相覆盖。
+最终输出 Test append OK!
+*/
+
+const WRITERS: usize = 4;
+const RECORDS: usize = 100;
+/// 记录格式 "w:nnnn\n"，末尾补齐到 RECORD_LEN 字节
+const RECORD_LEN: usize = 8;
+
+fn record(writer: usize, i: usize) -> [u8; RECORD_LEN] {
+    let mut rec = *b"w:0000 \n";
+    rec[0] = b'0' + writer as u8;
+    let mut n = i;
+    for d in rec[2..6].iter_mut().rev() {
+        *d = b'0' + (n % 10) as u8;
+        n /= 10;
+    }
+    rec
+}
+
+fn parse(rec: &[u8]) -> (usize, usize) {
+    assert_eq!(rec[1], b':');
+    assert_eq!(&rec[6..], b" \n");
+    let writer = (rec[0] - b'0') as usize;
+    let i = rec[2..6]
+        .iter()
+        .fold(0, |acc, &d| acc * 10 + (d - b'0') as usize);
+    (writer, i)
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let fname = "fname_append\0";
+    // 先写入一个头部，检验追加从文件末尾而不是偏移 0 开始
+    let fd = open(
+        fname,
+        OpenFlags::CREATE | OpenFlags::WRONLY | OpenFlags::TRUNC,
+    );
+    assert!(fd > 0);
+    assert_eq!(write(fd as usize, b"header:\n"), RECORD_LEN as isize);
+    close(fd as usize);
+
+    let mut pids = [0isize; WRITERS];
+    for (writer, pid) in pids.iter_mut().enumerate() {
+        *pid = fork();
+        if *pid == 0 {
+            let fd = open(fname, OpenFlags::WRONLY | OpenFlags::APPEND);
+            assert!(fd > 0);
+            for i in 0..RECORDS {
+                assert_eq!(write(fd as usize, &record(writer, i)), RECORD_LEN as isize);
+            }
+            close(fd as usize);
+            exit(0);
+        }
+        assert!(*pid > 0);
+    }
+    for &pid in pids.iter() {
+        let mut exit_code = 0;
+        assert_eq!(waitpid(pid as usize, &mut exit_code), pid);
+        assert_eq!(exit_code, 0);
+    }
+
+    let mut seen = [[false; RECORDS]; WRITERS];
+    let fd = open(fname, OpenFlags::RDONLY);
+    assert!(fd > 0);
+    let mut buf = [0u8; RECORD_LEN * 32];
+    let mut total = 0;
+    loop {
+        let len = read(fd as usize, &mut buf) as usize;
+        if len == 0 {
+            break;
+        }
+        // 读缓冲区长度是记录长度的整数倍，普通文件读取不会在记录中间截断
+        assert_eq!(len % RECORD_LEN, 0);
+        for rec in buf[..len].chunks(RECORD_LEN) {
+            if total == 0 {
+                assert_eq!(rec, b"header:\n");
+            } else {
+                let (writer, i) = parse(rec);
+                assert!(!seen[writer][i], "record {}:{} written twice", writer, i);
+                seen[writer][i] = true;
+            }
+            total += 1;
+        }
+    }
+    close(fd as usize);
+    assert_eq!(total, 1 + WRITERS * RECORDS);
+    assert!(seen.iter().all(|w| w.iter().all(|&s| s)));
+    assert_eq!(unlink(fname), 0);
+    println!("Test append OK!");
+    0
+}
@@ -0,0 +1,73 @@
+#![no_std]
+#![no_main]
+
+extern crate alloc;
+#[macro_use]
+extern crate user_lib;
+
+use alloc::format;
+use user_lib::{close, exec, fork, fstat, open, unlink, waitpid, OpenFlags, Stat};
+
+/*
+理想结果：带 CLOEXEC 打开的 fd 在 exec 之后被关闭，普通 fd 仍然可用；
+fork 本身不会关闭 CLOEXEC fd。
+本程序以参数 child <cloexec_fd> <plain_fd> 重新 exec 自身完成检查。
+最终输出 Test cloexec OK!
+*/
+
+fn is_open(fd: usize) -> bool {
+    let mut st = Stat::new();
+    fstat(fd, &mut st) == 0
+}
+
+fn parse_fd(arg: &str) -> usize {
+    arg.parse().expect("bad fd argument")
+}
+
+#[no_mangle]
+pub fn main(argc: usize, argv: &[&str]) -> i32 {
+    if argc == 4 && argv[1] == "child" {
+        let (cloexec_fd, plain_fd) = (parse_fd(argv[2]), parse_fd(argv[3]));
+        assert!(!is_open(cloexec_fd), "CLOEXEC fd survived exec");
+        assert!(is_open(plain_fd));
+        return 0;
+    }
+
+    let fname = "fname_cloexec\0";
+    let cloexec_fd = open(
+        fname,
+        OpenFlags::CREATE | OpenFlags::WRONLY | OpenFlags::CLOEXEC,
+    );
+    assert!(cloexec_fd > 0);
+    let plain_fd = open(fname, OpenFlags::RDONLY);
+    assert!(plain_fd > 0);
+    let (cloexec_fd, plain_fd) = (cloexec_fd as usize, plain_fd as usize);
+
+    let pid = fork();
+    if pid == 0 {
+        assert!(is_open(cloexec_fd));
+        let (cloexec_arg, plain_arg) = (format!("{}\0", cloexec_fd), format!("{}\0", plain_fd));
+        let args_addr = [
+            "ch6_cloexec\0".as_ptr(),
+            "child\0".as_ptr(),
+            cloexec_arg.as_ptr(),
+            plain_arg.as_ptr(),
+            core::ptr::null::<u8>(),
+        ];
+        exec("ch6_cloexec\0", &args_addr);
+        panic!("exec failed");
+    }
+    assert!(pid > 0);
+    let mut exit_code = 0;
+    assert_eq!(waitpid(pid as usize, &mut exit_code), pid);
+    assert_eq!(exit_code, 0);
+
+    // 父进程没有 exec，两个 fd 都还在
+    assert!(is_open(cloexec_fd));
+    assert!(is_open(plain_fd));
+    close(cloexec_fd);
+    close(plain_fd);
+    assert_eq!(unlink(fname), 0);
+    println!("Test cloexec OK!");
+    0
+}
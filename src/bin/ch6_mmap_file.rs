@@ -0,0 +1,118 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{close, mmap_with, munmap, open, read, write, MapFlags, OpenFlags, ProtFlags};
+
+/*
+理想结果：SHARED 映射的修改在 munmap 后通过 read 可见，
+PRIVATE 映射的修改不会写回文件，最终输出 Test mmap_file OK!
+*/
+
+const PAGE_SIZE: usize = 4096;
+const FILE_LEN: usize = PAGE_SIZE * 2;
+const NAME: &str = "mmap_file\0";
+
+fn expected(i: usize) -> u8 {
+    (i % 251) as u8
+}
+
+const CHUNK: usize = 256;
+
+/// 重新打开文件并逐块读取，检查第 i 个字节等于 `f(i)`
+fn check_file(f: impl Fn(usize) -> u8) {
+    let fd = open(NAME, OpenFlags::RDONLY);
+    assert!(fd > 0);
+    let fd = fd as usize;
+    let mut buf = [0u8; CHUNK];
+    let mut pos = 0;
+    while pos < FILE_LEN {
+        let n = read(fd, &mut buf);
+        assert!(n > 0);
+        for &b in buf[..n as usize].iter() {
+            assert_eq!(b, f(pos));
+            pos += 1;
+        }
+    }
+    assert_eq!(read(fd, &mut buf), 0);
+    close(fd);
+}
+
+/// 映射文件，对每个字节执行 `f`，然后解除映射
+fn with_mapping(fd: usize, flags: MapFlags, offset: usize, len: usize, f: impl Fn(&mut [u8])) {
+    let rw = ProtFlags::READ | ProtFlags::WRITE;
+    let addr = mmap_with(0, len, rw, flags, Some(fd), offset);
+    assert!(addr > 0);
+    let mem = unsafe { core::slice::from_raw_parts_mut(addr as *mut u8, len) };
+    f(mem);
+    assert_eq!(munmap(addr as usize, len), 0);
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let fd = open(NAME, OpenFlags::CREATE | OpenFlags::TRUNC | OpenFlags::RDWR);
+    assert!(fd > 0);
+    let fd = fd as usize;
+    let mut chunk = [0u8; CHUNK];
+    for start in (0..FILE_LEN).step_by(CHUNK) {
+        for (i, b) in chunk.iter_mut().enumerate() {
+            *b = expected(start + i);
+        }
+        assert_eq!(write(fd, &chunk), CHUNK as isize);
+    }
+
+    // 映射内容与文件一致
+    with_mapping(fd, MapFlags::SHARED, 0, FILE_LEN, |mem| {
+        for (i, &b) in mem.iter().enumerate() {
+            assert_eq!(b, expected(i));
+        }
+    });
+
+    // PRIVATE 映射的修改不写回
+    with_mapping(fd, MapFlags::PRIVATE, 0, FILE_LEN, |mem| {
+        mem.fill(0xff);
+        assert!(mem.iter().all(|&b| b == 0xff));
+    });
+    check_file(expected);
+
+    // SHARED 映射的修改在 munmap 后写回，偏移映射只影响第二页
+    with_mapping(fd, MapFlags::SHARED, PAGE_SIZE, PAGE_SIZE, |mem| {
+        for (i, b) in mem.iter_mut().enumerate() {
+            assert_eq!(*b, expected(PAGE_SIZE + i));
+            *b = !*b;
+        }
+    });
+    check_file(|i| {
+        if i < PAGE_SIZE {
+            expected(i)
+        } else {
+            !expected(i)
+        }
+    });
+
+    // 偏移未对齐、只读打开的文件不能建立可写的共享映射
+    let rw = ProtFlags::READ | ProtFlags::WRITE;
+    assert!(mmap_with(0, PAGE_SIZE, rw, MapFlags::SHARED, Some(fd), 1) < 0);
+    close(fd);
+    let ro_fd = open(NAME, OpenFlags::RDONLY);
+    assert!(ro_fd > 0);
+    let ro_fd = ro_fd as usize;
+    assert!(mmap_with(0, PAGE_SIZE, rw, MapFlags::SHARED, Some(ro_fd), 0) < 0);
+    let addr = mmap_with(
+        0,
+        PAGE_SIZE,
+        ProtFlags::READ,
+        MapFlags::SHARED,
+        Some(ro_fd),
+        0,
+    );
+    assert!(addr > 0);
+    assert_eq!(unsafe { *(addr as *const u8) }, expected(0));
+    assert_eq!(munmap(addr as usize, PAGE_SIZE), 0);
+    close(ro_fd);
+
+    println!("Test mmap_file OK!");
+    0
+}
@@ -0,0 +1,56 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{close, mkdir, open, open_with_mode, rmdir, symlink, unlink, write, OpenFlags};
+
+/*
+理想结果：CREATE|EXCL 对已存在的文件失败，DIRECTORY 只能打开目录，
+NOFOLLOW 拒绝打开符号链接，open_with_mode 能创建文件。
+最终输出 Test open_flags OK!
+*/
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let (file, dir, link) = ("open_flags_file\0", "open_flags_dir\0", "open_flags_link\0");
+    let excl = OpenFlags::CREATE | OpenFlags::EXCL | OpenFlags::WRONLY;
+
+    // EXCL：第一次创建成功，之后失败
+    let fd = open_with_mode(file, excl, 0o600);
+    assert!(fd > 0);
+    assert_eq!(write(fd as usize, b"data"), 4);
+    close(fd as usize);
+    assert!(open(file, excl) < 0);
+    assert!(open_with_mode(file, excl, 0o644) < 0);
+    // 不带 EXCL 时可以正常打开已存在的文件
+    let fd = open(file, OpenFlags::CREATE | OpenFlags::WRONLY);
+    assert!(fd > 0);
+    close(fd as usize);
+
+    // DIRECTORY
+    assert_eq!(mkdir(dir), 0);
+    let fd = open(dir, OpenFlags::RDONLY | OpenFlags::DIRECTORY);
+    assert!(fd > 0);
+    close(fd as usize);
+    assert!(open(file, OpenFlags::RDONLY | OpenFlags::DIRECTORY) < 0);
+    // 目录不能以写方式打开
+    assert!(open(dir, OpenFlags::WRONLY) < 0);
+
+    // NOFOLLOW
+    assert_eq!(symlink(file, link), 0);
+    let fd = open(link, OpenFlags::RDONLY);
+    assert!(fd > 0);
+    close(fd as usize);
+    assert!(open(link, OpenFlags::RDONLY | OpenFlags::NOFOLLOW) < 0);
+    let fd = open(file, OpenFlags::RDONLY | OpenFlags::NOFOLLOW);
+    assert!(fd > 0);
+    close(fd as usize);
+
+    assert_eq!(unlink(link), 0);
+    assert_eq!(unlink(file), 0);
+    assert_eq!(rmdir(dir), 0);
+    println!("Test open_flags OK!");
+    0
+}
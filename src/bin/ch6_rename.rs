@@ -0,0 +1,93 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{
+    close, link, mkdir, open, read, rename, rmdir, stat, unlink, write, OpenFlags, Stat,
+};
+
+/*
+理想结果：rename 可以覆盖已存在的文件、可以跨目录移动，
+移动后 inode 号与硬链接数保持不变，旧路径不再存在。
+最终输出 Test rename OK!
+*/
+
+fn create(path: &str, content: &str) {
+    let fd = open(
+        path,
+        OpenFlags::CREATE | OpenFlags::WRONLY | OpenFlags::TRUNC,
+    );
+    assert!(fd > 0);
+    assert_eq!(
+        write(fd as usize, content.as_bytes()),
+        content.len() as isize
+    );
+    close(fd as usize);
+}
+
+fn check_content(path: &str, content: &str) {
+    let fd = open(path, OpenFlags::RDONLY);
+    assert!(fd > 0);
+    let mut buf = [0u8; 64];
+    let len = read(fd as usize, &mut buf) as usize;
+    close(fd as usize);
+    assert_eq!(&buf[..len], content.as_bytes());
+}
+
+fn stat_of(path: &str) -> Stat {
+    let mut st = Stat::new();
+    assert_eq!(stat(path, &mut st), 0);
+    st
+}
+
+fn exists(path: &str) -> bool {
+    let mut st = Stat::new();
+    stat(path, &mut st) == 0
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let (a, b) = ("rename_a\0", "rename_b\0");
+    create(a, "content of a");
+    create(b, "content of b");
+    let ino = stat_of(a).ino;
+
+    // 覆盖已存在的文件
+    assert_eq!(rename(a, b), 0);
+    assert!(!exists(a));
+    assert_eq!(stat_of(b).ino, ino);
+    check_content(b, "content of a");
+    // 源文件不存在时失败
+    assert!(rename(a, b) < 0);
+
+    // 带硬链接的文件，改名后链接数与 inode 不变
+    let (l, c) = ("rename_link\0", "rename_c\0");
+    assert_eq!(link(b, l), 0);
+    assert_eq!(stat_of(b).nlink, 2);
+    assert_eq!(rename(b, c), 0);
+    let st = stat_of(c);
+    assert_eq!(st.ino, ino);
+    assert_eq!(st.nlink, 2);
+    assert_eq!(stat_of(l).ino, ino);
+
+    // 跨目录移动并移回
+    let (dir, moved) = ("rename_dir\0", "rename_dir/c\0");
+    assert_eq!(mkdir(dir), 0);
+    assert_eq!(rename(c, moved), 0);
+    assert!(!exists(c));
+    assert_eq!(stat_of(moved).ino, ino);
+    check_content(moved, "content of a");
+    // 非空目录不能删除
+    assert!(rmdir(dir) < 0);
+    assert_eq!(rename(moved, c), 0);
+    assert_eq!(stat_of(c).ino, ino);
+    assert_eq!(rmdir(dir), 0);
+
+    assert_eq!(unlink(c), 0);
+    assert_eq!(stat_of(l).nlink, 1);
+    assert_eq!(unlink(l), 0);
+    println!("Test rename OK!");
+    0
+}
@@ -0,0 +1,66 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::time::Duration;
+use user_lib::{close, fstat, open, sleep, unlink, write, OpenFlags, Stat};
+
+/*
+理想结果：fstat 返回的 size/blocks 随写入增长，O_TRUNC 重新打开后归零；
+写入后 mtime 前进，ctime 不早于 mtime。
+最终输出 Test stat_size OK!
+*/
+
+const CHUNK: usize = 1000;
+
+fn stat(fd: usize) -> Stat {
+    let mut st = Stat::new();
+    assert_eq!(fstat(fd, &mut st), 0);
+    st
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let fname = "fname_stat_size\0";
+    let fd = open(fname, OpenFlags::CREATE | OpenFlags::WRONLY);
+    assert!(fd > 0);
+    let fd = fd as usize;
+    let st = stat(fd);
+    assert_eq!(st.size, 0);
+    assert_eq!(st.blocks, 0);
+    assert!(st.blksize > 0);
+    assert!(st.mtime > 0);
+
+    let chunk = [b'x'; CHUNK];
+    for i in 1..=5 {
+        assert_eq!(write(fd, &chunk), CHUNK as isize);
+        let st = stat(fd);
+        assert_eq!(st.size, (i * CHUNK) as u64);
+        // blocks 以 512 字节为单位，至少能容纳全部数据
+        assert!(st.blocks * 512 >= st.size);
+    }
+
+    let before = stat(fd);
+    sleep(Duration::from_millis(20)).unwrap();
+    assert_eq!(write(fd, b"more"), 4);
+    let after = stat(fd);
+    assert_eq!(after.size, before.size + 4);
+    assert!(after.modified() > before.modified());
+    assert!(after.changed() >= after.modified());
+    close(fd);
+
+    // O_TRUNC 把文件截断为 0
+    let fd = open(fname, OpenFlags::TRUNC | OpenFlags::WRONLY);
+    assert!(fd > 0);
+    let st = stat(fd as usize);
+    assert_eq!(st.size, 0);
+    assert_eq!(st.blocks, 0);
+    assert!(st.modified() >= after.modified());
+    close(fd as usize);
+
+    assert_eq!(unlink(fname), 0);
+    println!("Test stat_size OK!");
+    0
+}
@@ -0,0 +1,114 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{
+    close, lstat, mkdir, open, read, readlink, rmdir, stat, symlink, unlink, write, OpenFlags,
+    Stat, StatMode,
+};
+
+/*
+理想结果：open/stat 跟随符号链接，lstat/readlink 描述链接本身；
+悬空链接可以创建但无法打开，相对目标相对于链接所在目录解析，
+可以链接到目录，循环链接打开失败（ELOOP）。
+最终输出 Test symlink OK!
+*/
+
+fn create(path: &str, content: &str) {
+    let fd = open(
+        path,
+        OpenFlags::CREATE | OpenFlags::WRONLY | OpenFlags::TRUNC,
+    );
+    assert!(fd > 0);
+    assert_eq!(
+        write(fd as usize, content.as_bytes()),
+        content.len() as isize
+    );
+    close(fd as usize);
+}
+
+fn check_content(path: &str, content: &str) {
+    let fd = open(path, OpenFlags::RDONLY);
+    assert!(fd > 0);
+    let mut buf = [0u8; 64];
+    let len = read(fd as usize, &mut buf) as usize;
+    close(fd as usize);
+    assert_eq!(&buf[..len], content.as_bytes());
+}
+
+fn check_link(path: &str, target: &str) {
+    let mut st = Stat::new();
+    assert_eq!(lstat(path, &mut st), 0);
+    assert_eq!(st.mode.file_type(), StatMode::LNK);
+    // LNK 的位包含 FILE 的位，类型必须整体比较
+    assert_ne!(st.mode.file_type(), StatMode::FILE);
+    assert_eq!(st.size, target.len() as u64);
+    let mut buf = [0u8; 64];
+    let len = readlink(path, &mut buf);
+    assert_eq!(len, target.len() as isize);
+    assert_eq!(&buf[..len as usize], target.as_bytes());
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    // 指向普通文件
+    let (file, link) = ("symlink_file\0", "symlink_to_file\0");
+    create(file, "hello symlink");
+    assert_eq!(symlink(file, link), 0);
+    check_link(link, "symlink_file");
+    check_content(link, "hello symlink");
+    let (mut st, mut lst) = (Stat::new(), Stat::new());
+    assert_eq!(stat(link, &mut st), 0);
+    assert_eq!(stat(file, &mut lst), 0);
+    assert_eq!(st.mode.file_type(), StatMode::FILE);
+    assert_eq!(st.ino, lst.ino);
+    // 链接已存在时不能重复创建
+    assert!(symlink(file, link) < 0);
+    // 不是符号链接的路径不能 readlink
+    assert!(readlink(file, &mut [0u8; 16]) < 0);
+
+    // 悬空链接
+    let dangling = "symlink_dangling\0";
+    assert_eq!(symlink("symlink_missing\0", dangling), 0);
+    check_link(dangling, "symlink_missing");
+    assert!(open(dangling, OpenFlags::RDONLY) < 0);
+    assert!(stat(dangling, &mut st) < 0);
+
+    // 指向目录，以及相对于链接所在目录的目标
+    let (dir, inner, dir_link) = ("symlink_dir\0", "symlink_dir/inner\0", "symlink_to_dir\0");
+    assert_eq!(mkdir(dir), 0);
+    create(inner, "inside dir");
+    assert_eq!(symlink(dir, dir_link), 0);
+    assert_eq!(stat(dir_link, &mut st), 0);
+    assert_eq!(st.mode.file_type(), StatMode::DIR);
+    check_content("symlink_to_dir/inner\0", "inside dir");
+    let rel = "symlink_dir/rel\0";
+    assert_eq!(symlink("inner\0", rel), 0);
+    check_link(rel, "inner");
+    check_content(rel, "inside dir");
+    check_content("symlink_to_dir/rel\0", "inside dir");
+
+    // 循环链接
+    let (a, b) = ("symlink_loop_a\0", "symlink_loop_b\0");
+    assert_eq!(symlink(b, a), 0);
+    assert_eq!(symlink(a, b), 0);
+    assert!(open(a, OpenFlags::RDONLY) < 0);
+    assert!(stat(a, &mut st) < 0);
+    check_link(a, "symlink_loop_b");
+    let self_loop = "symlink_self\0";
+    assert_eq!(symlink(self_loop, self_loop), 0);
+    assert!(open(self_loop, OpenFlags::RDONLY) < 0);
+
+    // unlink 删除链接本身而不是目标
+    for path in [link, dangling, rel, dir_link, a, b, self_loop] {
+        assert_eq!(unlink(path), 0);
+    }
+    check_content(file, "hello symlink");
+    assert_eq!(unlink(inner), 0);
+    assert_eq!(rmdir(dir), 0);
+    assert_eq!(unlink(file), 0);
+    println!("Test symlink OK!");
+    0
+}
@@ -0,0 +1,87 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{
+    close, fstat, ftruncate, open, read, stat, truncate, unlink, write, OpenFlags, Stat,
+};
+
+/*
+理想结果：ftruncate/truncate 可以缩小和扩大文件，
+缩小后只保留前缀，扩大后新增部分全为 0。
+最终输出 Test truncate OK!
+*/
+
+const CHUNK: usize = 256;
+
+fn size_of_path(path: &str) -> u64 {
+    let mut st = Stat::new();
+    assert_eq!(stat(path, &mut st), 0);
+    st.size
+}
+
+/// 读出整个文件，检查前 `prefix` 字节为 b'a'、其余为 0，返回文件长度
+fn check(path: &str, prefix: usize) -> usize {
+    let fd = open(path, OpenFlags::RDONLY);
+    assert!(fd > 0);
+    let mut buf = [0u8; CHUNK];
+    let mut total = 0;
+    loop {
+        let len = read(fd as usize, &mut buf);
+        assert!(len >= 0);
+        if len == 0 {
+            break;
+        }
+        for (i, &b) in buf[..len as usize].iter().enumerate() {
+            let expected = if total + i < prefix { b'a' } else { 0 };
+            assert_eq!(b, expected, "offset {}", total + i);
+        }
+        total += len as usize;
+    }
+    close(fd as usize);
+    total
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let fname = "fname_truncate\0";
+    let fd = open(
+        fname,
+        OpenFlags::CREATE | OpenFlags::RDWR | OpenFlags::TRUNC,
+    );
+    assert!(fd > 0);
+    let fd = fd as usize;
+    let chunk = [b'a'; CHUNK];
+    for _ in 0..4 {
+        assert_eq!(write(fd, &chunk), CHUNK as isize);
+    }
+
+    // 缩小
+    assert_eq!(ftruncate(fd, 100), 0);
+    let mut st = Stat::new();
+    assert_eq!(fstat(fd, &mut st), 0);
+    assert_eq!(st.size, 100);
+    assert_eq!(check(fname, 100), 100);
+
+    // 扩大，跨越多个块，新增部分为 0
+    assert_eq!(truncate(fname, 5000), 0);
+    assert_eq!(size_of_path(fname), 5000);
+    assert_eq!(check(fname, 100), 5000);
+
+    // 缩小后再扩大，之前被截掉的数据不能重新出现
+    assert_eq!(ftruncate(fd, 10), 0);
+    assert_eq!(ftruncate(fd, 300), 0);
+    assert_eq!(check(fname, 10), 300);
+
+    assert_eq!(truncate(fname, 0), 0);
+    assert_eq!(size_of_path(fname), 0);
+    close(fd);
+
+    // 不存在的文件
+    assert!(truncate("fname_truncate_missing\0", 0) < 0);
+    assert_eq!(unlink(fname), 0);
+    println!("Test truncate OK!");
+    0
+}
@@ -0,0 +1,116 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{
+    close, dup2, dup3, exit, fork, get_cloexec, pipe, read, waitpid, write, OpenFlags, STDERR,
+    STDIN, STDOUT,
+};
+
+/*
+理想结果：dup2 可以把管道直接重定向到 stdin/stdout/stderr，
+目标 fd 已打开时先被关闭；dup2(fd, fd) 不做任何事，dup3(fd, fd) 失败；
+源 fd 无效或目标 fd 超出范围时失败且不影响目标 fd。
+最终输出 Test dup2 OK!
+*/
+
+const BAD_FD: usize = 100000;
+
+/// 在子进程中把管道写端 dup2 到 `target` 后运行 `f`，返回父进程从管道读到的内容
+fn capture(target: usize, f: fn(), buf: &mut [u8]) -> usize {
+    let mut pipe_fd = [0usize; 2];
+    assert_eq!(pipe(&mut pipe_fd), 0);
+    let pid = fork();
+    if pid == 0 {
+        close(pipe_fd[0]);
+        assert_eq!(dup2(pipe_fd[1], target), target as isize);
+        close(pipe_fd[1]);
+        f();
+        exit(0);
+    }
+    close(pipe_fd[1]);
+    let mut len = 0;
+    loop {
+        match read(pipe_fd[0], &mut buf[len..]) {
+            n if n <= 0 => break,
+            n => len += n as usize,
+        }
+    }
+    close(pipe_fd[0]);
+    let mut exit_code = 0;
+    assert_eq!(waitpid(pid as usize, &mut exit_code), pid);
+    assert_eq!(exit_code, 0);
+    len
+}
+
+fn redirect_stdin() {
+    let mut pipe_fd = [0usize; 2];
+    assert_eq!(pipe(&mut pipe_fd), 0);
+    assert_eq!(write(pipe_fd[1], b"from pipe"), 9);
+    close(pipe_fd[1]);
+    let pid = fork();
+    if pid == 0 {
+        assert_eq!(dup2(pipe_fd[0], STDIN), STDIN as isize);
+        close(pipe_fd[0]);
+        let mut buf = [0u8; 16];
+        assert_eq!(read(STDIN, &mut buf), 9);
+        assert_eq!(&buf[..9], b"from pipe");
+        // 写端已全部关闭，读到 EOF
+        assert_eq!(read(STDIN, &mut buf), 0);
+        exit(0);
+    }
+    close(pipe_fd[0]);
+    let mut exit_code = 0;
+    assert_eq!(waitpid(pid as usize, &mut exit_code), pid);
+    assert_eq!(exit_code, 0);
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let mut buf = [0u8; 64];
+    let len = capture(STDOUT, || println!("to stdout"), &mut buf);
+    assert_eq!(&buf[..len], b"to stdout\n");
+    let len = capture(STDERR, || eprintln!("to stderr"), &mut buf);
+    assert_eq!(&buf[..len], b"to stderr\n");
+    redirect_stdin();
+
+    // 目标 fd 已打开：原来的管道写端被关闭，写入转到新管道
+    let (mut a, mut b) = ([0usize; 2], [0usize; 2]);
+    assert_eq!(pipe(&mut a), 0);
+    assert_eq!(pipe(&mut b), 0);
+    assert_eq!(dup2(a[1], b[1]), b[1] as isize);
+    assert_eq!(write(b[1], b"x"), 1);
+    close(a[1]);
+    close(b[1]);
+    assert_eq!(read(a[0], &mut buf), 1);
+    assert_eq!(buf[0], b'x');
+    // b 唯一的写端在 dup2 时被关闭，读端看到 EOF
+    assert_eq!(read(b[0], &mut buf), 0);
+    close(b[0]);
+
+    // 自身复制
+    assert_eq!(dup2(a[0], a[0]), a[0] as isize);
+    assert!(dup3(a[0], a[0], OpenFlags::empty()) < 0);
+    assert!(dup2(b[0], b[0]) < 0);
+
+    // 非法的源或目标
+    assert!(dup2(a[0], BAD_FD) < 0);
+    assert!(dup2(BAD_FD, a[0]) < 0);
+    assert!(dup2(b[0], a[0]) < 0);
+    // 失败的 dup2 不会关闭目标 fd
+    assert_eq!(dup2(a[0], a[0]), a[0] as isize);
+
+    // dup3 带 CLOEXEC
+    let target = a[0] + 8;
+    assert_eq!(get_cloexec(a[0]), Some(false));
+    assert_eq!(dup3(a[0], target, OpenFlags::CLOEXEC), target as isize);
+    // 标志只设置在新 fd 上
+    assert_eq!(get_cloexec(target), Some(true));
+    assert_eq!(get_cloexec(a[0]), Some(false));
+    close(target);
+    close(a[0]);
+    println!("Test dup2 OK!");
+    0
+}
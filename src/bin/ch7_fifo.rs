@@ -0,0 +1,188 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::time::{Duration, Instant};
+use user_lib::{
+    close, exec, exit, fork, mkfifo, open, read, sleep, stat, unlink, waitpid, write, OpenFlags,
+    Stat, StatMode,
+};
+
+/*
+理想结果：mkfifo 创建的命名管道可以连接没有亲缘关系的进程（通过 exec 启动、不共享 fd）；
+阻塞 open 要等到另一端也被打开，NONBLOCK 打开读端立即成功、打开写端失败；
+多个读者各自读到完整的记录且没有重复；使用中 unlink 不影响已打开的两端。
+最终输出 Test fifo OK!
+*/
+
+const FIFO: &str = "fifo_test\0";
+const GREETING: &[u8] = b"hello through fifo";
+const OPEN_DELAY_MS: u64 = 100;
+const READERS: usize = 2;
+const RECORDS: usize = 100;
+const RECORD_LEN: usize = 8;
+
+/// fork 后在子进程中以 `role` 参数重新 exec 本程序，返回子进程 pid
+fn spawn_role(role: &str) -> isize {
+    let pid = fork();
+    if pid == 0 {
+        exec(
+            "ch7_fifo\0",
+            &[
+                "ch7_fifo\0".as_ptr(),
+                role.as_ptr(),
+                core::ptr::null::<u8>(),
+            ],
+        );
+        panic!("exec failed");
+    }
+    assert!(pid > 0);
+    pid
+}
+
+fn wait_for(pid: isize) -> i32 {
+    let mut exit_code = 0;
+    assert_eq!(waitpid(pid as usize, &mut exit_code), pid);
+    exit_code
+}
+
+/// exec 出来的写者：稍等片刻后打开写端并写入问候语
+fn greeter() -> i32 {
+    sleep(Duration::from_millis(OPEN_DELAY_MS)).unwrap();
+    let fd = open(FIFO, OpenFlags::WRONLY);
+    assert!(fd > 0);
+    assert_eq!(write(fd as usize, GREETING), GREETING.len() as isize);
+    close(fd as usize);
+    0
+}
+
+/// exec 出来的读者：读取定长记录直到 EOF，以读到的记录数作为退出码
+fn record_reader() -> i32 {
+    let fd = open(FIFO, OpenFlags::RDONLY);
+    assert!(fd > 0);
+    let mut count = 0;
+    let mut rec = [0u8; RECORD_LEN];
+    loop {
+        let n = read(fd as usize, &mut rec);
+        if n == 0 {
+            break;
+        }
+        // 写入是原子的，每次读取恰好得到一条完整记录
+        assert_eq!(n, RECORD_LEN as isize);
+        assert!(rec.iter().all(|&b| b == rec[0]));
+        count += 1;
+    }
+    close(fd as usize);
+    count
+}
+
+fn open_blocks_until_writer() {
+    let pid = spawn_role("greeter\0");
+    let start = Instant::now();
+    let fd = open(FIFO, OpenFlags::RDONLY);
+    assert!(fd > 0);
+    assert!(start.elapsed() >= Duration::from_millis(OPEN_DELAY_MS / 2));
+    let mut buf = [0u8; 64];
+    let mut len = 0;
+    loop {
+        match read(fd as usize, &mut buf[len..]) {
+            n if n <= 0 => break,
+            n => len += n as usize,
+        }
+    }
+    assert_eq!(&buf[..len], GREETING);
+    close(fd as usize);
+    assert_eq!(wait_for(pid), 0);
+}
+
+fn nonblocking_open() {
+    // 没有读者时 NONBLOCK 打开写端失败
+    assert!(open(FIFO, OpenFlags::WRONLY | OpenFlags::NONBLOCK) < 0);
+    // NONBLOCK 打开读端立即成功，之后写端也可以打开
+    let rd = open(FIFO, OpenFlags::RDONLY | OpenFlags::NONBLOCK);
+    assert!(rd > 0);
+    let wr = open(FIFO, OpenFlags::WRONLY | OpenFlags::NONBLOCK);
+    assert!(wr > 0);
+    assert_eq!(write(wr as usize, b"ok"), 2);
+    let mut buf = [0u8; 4];
+    assert_eq!(read(rd as usize, &mut buf), 2);
+    close(wr as usize);
+    close(rd as usize);
+}
+
+fn multiple_readers() {
+    // 先打开写端，读者的阻塞 open 就不会因为写者已经写完退出而永远等待；
+    // 借助一个从不读取的非阻塞读端完成打开。
+    // 两者都带 CLOEXEC，否则 exec 出的读者继承写端，永远读不到 EOF
+    let idle = open(
+        FIFO,
+        OpenFlags::RDONLY | OpenFlags::NONBLOCK | OpenFlags::CLOEXEC,
+    );
+    assert!(idle > 0);
+    let fd = open(FIFO, OpenFlags::WRONLY | OpenFlags::CLOEXEC);
+    assert!(fd > 0);
+    let mut pids = [0isize; READERS];
+    for pid in pids.iter_mut() {
+        *pid = spawn_role("reader\0");
+    }
+    for i in 0..RECORDS {
+        let rec = [b'a' + (i % 26) as u8; RECORD_LEN];
+        assert_eq!(write(fd as usize, &rec), RECORD_LEN as isize);
+    }
+    close(fd as usize);
+    let total: i32 = pids.iter().map(|&pid| wait_for(pid)).sum();
+    assert_eq!(total, RECORDS as i32);
+    close(idle as usize);
+}
+
+fn unlink_while_open() {
+    let rd = open(FIFO, OpenFlags::RDONLY | OpenFlags::NONBLOCK);
+    assert!(rd > 0);
+    let wr = open(FIFO, OpenFlags::WRONLY);
+    assert!(wr > 0);
+    assert_eq!(unlink(FIFO), 0);
+    let mut st = Stat::new();
+    assert!(stat(FIFO, &mut st) < 0);
+    // 已打开的两端仍然相连
+    assert_eq!(write(wr as usize, b"still here"), 10);
+    let mut buf = [0u8; 16];
+    assert_eq!(read(rd as usize, &mut buf), 10);
+    assert_eq!(&buf[..10], b"still here");
+    // 同名新建的 FIFO 与旧的无关
+    assert_eq!(mkfifo(FIFO), 0);
+    let other = open(FIFO, OpenFlags::RDONLY | OpenFlags::NONBLOCK);
+    assert!(other > 0);
+    assert_eq!(write(wr as usize, b"x"), 1);
+    assert!(read(other as usize, &mut buf) <= 0);
+    assert_eq!(read(rd as usize, &mut buf), 1);
+    close(other as usize);
+    close(wr as usize);
+    close(rd as usize);
+}
+
+#[no_mangle]
+pub fn main(argc: usize, argv: &[&str]) -> i32 {
+    if argc == 2 {
+        match argv[1] {
+            "greeter" => exit(greeter()),
+            "reader" => exit(record_reader()),
+            _ => panic!("unknown role {}", argv[1]),
+        }
+    }
+    unlink(FIFO);
+    assert_eq!(mkfifo(FIFO), 0);
+    assert!(mkfifo(FIFO) < 0);
+    let mut st = Stat::new();
+    assert_eq!(stat(FIFO, &mut st), 0);
+    assert_eq!(st.mode.file_type(), StatMode::FIFO);
+
+    open_blocks_until_writer();
+    nonblocking_open();
+    multiple_readers();
+    unlink_while_open();
+    assert_eq!(unlink(FIFO), 0);
+    println!("Test fifo OK!");
+    0
+}
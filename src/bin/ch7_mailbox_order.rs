@@ -0,0 +1,107 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use log::debug;
+use user_lib::logging;
+use user_lib::{exit, fork, getpid, mail_read_with_flags, mail_write, waitpid, yield_, MailFlags};
+
+const SENDERS: usize = 4;
+const MESSAGES: usize = 12;
+
+/// 邮箱满时让出 CPU 后重试
+fn send_retry(pid: usize, buf: &[u8]) {
+    loop {
+        let wrote = mail_write(pid, buf);
+        if wrote >= 0 {
+            assert_eq!(wrote, buf.len() as isize);
+            return;
+        }
+        yield_();
+    }
+}
+
+fn recv_from(buf: &mut [u8]) -> (usize, usize) {
+    let (len, sender) = mail_read_with_flags(buf, MailFlags::BLOCK);
+    assert!(len >= 0);
+    (len as usize, sender)
+}
+
+fn fork_sender(id: usize, parent: usize) -> usize {
+    let pid = fork();
+    if pid == 0 {
+        for seq in 0..MESSAGES {
+            send_retry(parent, &[id as u8, seq as u8]);
+        }
+        exit(0);
+    }
+    assert!(pid > 0);
+    pid as usize
+}
+
+fn wait_ok(pid: usize) {
+    let mut code = 0;
+    assert_eq!(waitpid(pid, &mut code), pid as isize);
+    assert_eq!(code, 0);
+}
+
+/// 多个发送者并发发送：每条消息带有正确的发送者进程号，且同一发送者的消息保持顺序
+fn concurrent_senders() {
+    let parent = getpid() as usize;
+    let mut pids = [0usize; SENDERS];
+    for (id, pid) in pids.iter_mut().enumerate() {
+        *pid = fork_sender(id, parent);
+    }
+    let mut next_seq = [0usize; SENDERS];
+    let mut buf = [0u8; 8];
+    for _ in 0..SENDERS * MESSAGES {
+        let (len, sender) = recv_from(&mut buf);
+        assert_eq!(len, 2);
+        let id = buf[0] as usize;
+        debug!("[接收] 来自 pid {} 的消息 {}:{}", sender, id, buf[1]);
+        assert_eq!(sender, pids[id]);
+        assert_eq!(buf[1] as usize, next_seq[id]);
+        next_seq[id] += 1;
+    }
+    for &pid in pids.iter() {
+        wait_ok(pid);
+    }
+}
+
+/// 发送者依次运行完毕后再统一读取：消息按到达顺序出队
+fn sequential_senders() {
+    let parent = getpid() as usize;
+    let mut pids = [0usize; SENDERS];
+    for (id, pid) in pids.iter_mut().enumerate() {
+        let child = fork();
+        if child == 0 {
+            assert_eq!(mail_write(parent, &[id as u8]), 1);
+            exit(0);
+        }
+        *pid = child as usize;
+        wait_ok(*pid);
+    }
+    let mut buf = [0u8; 8];
+    for (id, &pid) in pids.iter().enumerate() {
+        let (len, sender) = recv_from(&mut buf);
+        assert_eq!(len, 1);
+        assert_eq!(buf[0] as usize, id);
+        assert_eq!(sender, pid);
+    }
+}
+
+#[no_mangle]
+pub fn main(_argc: usize, argv: &[&str]) -> i32 {
+    logging::parse_args(argv);
+    concurrent_senders();
+    sequential_senders();
+    // 发给自己的消息，发送者就是自己
+    let self_pid = getpid() as usize;
+    assert_eq!(mail_write(self_pid, b"me"), 2);
+    let mut buf = [0u8; 4];
+    assert_eq!(recv_from(&mut buf), (2, self_pid));
+    println!("\x1b[32mch7_mailbox_order 测试通过\x1b[0m");
+    0
+}
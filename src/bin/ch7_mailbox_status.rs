@@ -0,0 +1,95 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use log::debug;
+use user_lib::logging;
+use user_lib::time::{Duration, Instant};
+use user_lib::{
+    exit, fork, getpid, mail_read, mail_read_blocking, mail_read_with_flags, mail_status,
+    mail_write, sleep, waitpid, MailFlags, MailStatus,
+};
+
+const MAILBOX_CAPACITY: usize = 16;
+const SEND_DELAY_MS: u64 = 50;
+
+fn status_of(pid: usize) -> MailStatus {
+    let mut status = MailStatus::new();
+    assert_eq!(mail_status(pid, &mut status), 0);
+    assert_eq!(status.pending + status.free, MAILBOX_CAPACITY);
+    status
+}
+
+/// 填满自己的邮箱，检查计数与队列满时的错误
+fn fill_and_drain() {
+    let self_pid = getpid() as usize;
+    assert_eq!(status_of(self_pid).pending, 0);
+    for i in 0..MAILBOX_CAPACITY {
+        assert_eq!(mail_write(self_pid, &[i as u8]), 1);
+        let status = status_of(self_pid);
+        assert_eq!(status.pending, i + 1);
+        debug!("[状态] 已入队 {}，剩余 {}", status.pending, status.free);
+    }
+    assert_eq!(status_of(self_pid).free, 0);
+    assert_eq!(mail_write(self_pid, &[0xff]), -1);
+    // 写入失败不改变邮箱内容
+    assert_eq!(status_of(self_pid).pending, MAILBOX_CAPACITY);
+
+    let mut buf = [0u8; 4];
+    for i in 0..MAILBOX_CAPACITY {
+        assert_eq!(mail_read(&mut buf), 1);
+        assert_eq!(buf[0], i as u8);
+        assert_eq!(status_of(self_pid).pending, MAILBOX_CAPACITY - i - 1);
+    }
+    assert_eq!(mail_read(&mut buf), -1);
+    // 非阻塞读取空邮箱不会阻塞
+    assert_eq!(mail_read_with_flags(&mut buf, MailFlags::empty()).0, -1);
+}
+
+/// 阻塞读取在子进程稍后发送消息时被唤醒，同时可以查询其他进程的邮箱
+fn blocking_wakeup() {
+    let parent = getpid() as usize;
+    let pid = fork();
+    if pid == 0 {
+        sleep(Duration::from_millis(SEND_DELAY_MS)).unwrap();
+        // 父进程阻塞在空邮箱上
+        assert_eq!(status_of(parent).pending, 0);
+        assert_eq!(mail_write(parent, b"wake"), 4);
+        // 等待父进程查询完本进程的邮箱后再退出
+        let mut buf = [0u8; 4];
+        assert_eq!(mail_read_blocking(&mut buf), 2);
+        exit(0);
+    }
+    let start = Instant::now();
+    let mut buf = [0u8; 8];
+    let (len, sender) = mail_read_with_flags(&mut buf, MailFlags::BLOCK);
+    assert_eq!(len, 4);
+    debug!("[阻塞] 等待 {:?} 后收到消息", start.elapsed());
+    assert!(start.elapsed() >= Duration::from_millis(SEND_DELAY_MS / 2));
+    assert_eq!(&buf[..4], b"wake");
+    assert_eq!(sender, pid as usize);
+
+    // 子进程仍在运行，其邮箱为空
+    assert_eq!(status_of(pid as usize).pending, 0);
+    assert_eq!(mail_write(pid as usize, b"ok"), 2);
+    let mut code = 0;
+    assert_eq!(waitpid(pid as usize, &mut code), pid);
+    assert_eq!(code, 0);
+
+    // 已有消息时阻塞读取立即返回
+    assert_eq!(mail_write(parent, b"x"), 1);
+    assert_eq!(mail_read_blocking(&mut buf), 1);
+}
+
+#[no_mangle]
+pub fn main(_argc: usize, argv: &[&str]) -> i32 {
+    logging::parse_args(argv);
+    fill_and_drain();
+    blocking_wakeup();
+    let mut status = MailStatus::new();
+    assert_eq!(mail_status(usize::MAX, &mut status), -1);
+    println!("\x1b[32mch7_mailbox_status 测试通过\x1b[0m");
+    0
+}
@@ -0,0 +1,241 @@
+#![no_std]
+#![no_main]
+
+extern crate alloc;
+#[macro_use]
+extern crate user_lib;
+
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use log::debug;
+use user_lib::logging;
+use user_lib::mailbox::{MailError, Mailbox, Message, Wire, MAX_MSG_LEN, MAX_PAYLOAD_LEN};
+use user_lib::{exit, fork, getpid, mail_write, waitpid};
+
+const BLOB_LEN: usize = 1000;
+
+#[derive(Debug, PartialEq)]
+struct Add(u64, u64);
+
+#[derive(Debug, PartialEq)]
+struct Sum(u64);
+
+#[derive(Debug, PartialEq)]
+struct Note(String);
+
+#[derive(Debug, PartialEq)]
+struct Blob {
+    seq: u32,
+    data: Vec<u8>,
+}
+
+#[derive(Debug, PartialEq)]
+struct Shutdown;
+
+impl Wire for Add {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.0.encode(out);
+        self.1.encode(out);
+    }
+
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        Some(Add(u64::decode(input)?, u64::decode(input)?))
+    }
+}
+
+impl Wire for Sum {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.0.encode(out);
+    }
+
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        u64::decode(input).map(Sum)
+    }
+}
+
+impl Wire for Note {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.0.encode(out);
+    }
+
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        String::decode(input).map(Note)
+    }
+}
+
+impl Wire for Blob {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.seq.encode(out);
+        self.data.encode(out);
+    }
+
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        Some(Blob {
+            seq: u32::decode(input)?,
+            data: Vec::decode(input)?,
+        })
+    }
+}
+
+impl Wire for Shutdown {
+    fn encode(&self, _out: &mut Vec<u8>) {}
+
+    fn decode(_input: &mut &[u8]) -> Option<Self> {
+        Some(Shutdown)
+    }
+}
+
+impl Message for Add {
+    const KIND: u16 = 1;
+}
+
+impl Message for Sum {
+    const KIND: u16 = 2;
+}
+
+impl Message for Note {
+    const KIND: u16 = 3;
+}
+
+impl Message for Blob {
+    const KIND: u16 = 4;
+}
+
+impl Message for Shutdown {
+    const KIND: u16 = 5;
+}
+
+fn blob() -> Blob {
+    Blob {
+        seq: 7,
+        data: (0..BLOB_LEN).map(|i| (i * 31) as u8).collect(),
+    }
+}
+
+fn run_client(server: usize) -> i32 {
+    let mut mailbox = Mailbox::new();
+    let sum: Sum = mailbox.call(server, &Add(2, 40)).unwrap();
+    assert_eq!(sum, Sum(42));
+    debug!("[客户端] 2 + 40 = {}", sum.0);
+
+    // 服务端先处理第二个 Add，Note 需要在服务端排队等待
+    mailbox
+        .send(server, &Note(String::from("queued note")))
+        .unwrap();
+    let sum: Sum = mailbox.call(server, &Add(u64::MAX - 1, 1)).unwrap();
+    assert_eq!(sum, Sum(u64::MAX));
+
+    // 超过 MAX_MSG_LEN 的负载在两个方向上都被分片与重组
+    let echoed: Blob = mailbox.call(server, &blob()).unwrap();
+    debug!("[客户端] 收到回显 {} 字节", echoed.data.len());
+    assert_eq!(echoed, blob());
+
+    mailbox.send(server, &Shutdown).unwrap();
+    0
+}
+
+fn run_server(client: usize) {
+    let mut mailbox = Mailbox::new();
+    for _ in 0..2 {
+        let req = mailbox.recv::<Add>().unwrap();
+        assert_eq!(req.sender, client);
+        debug!("[服务端] 请求 {} 来自 pid {}", req.id, req.sender);
+        let sum = Sum(req.msg.0.wrapping_add(req.msg.1));
+        mailbox.reply(&req, &sum).unwrap();
+    }
+    let note = mailbox.recv::<Note>().unwrap();
+    assert_eq!(note.sender, client);
+    assert_eq!(note.msg.0, "queued note");
+
+    let req = mailbox.recv::<Blob>().unwrap();
+    assert!(req.msg.data.len() > MAX_MSG_LEN);
+    assert_eq!(req.msg, blob());
+    mailbox.reply(&req, &req.msg).unwrap();
+
+    let shutdown = mailbox.recv::<Shutdown>().unwrap();
+    assert_eq!(shutdown.sender, client);
+}
+
+/// 绕过 `Mailbox` 直接向自己写入一个分片
+fn raw_fragment(index: u16, count: u16, total_len: u32, payload_len: usize) {
+    let mut frag = Vec::new();
+    Note::KIND.encode(&mut frag);
+    0u16.encode(&mut frag); // flags 与填充
+    7u32.encode(&mut frag); // id
+    index.encode(&mut frag);
+    count.encode(&mut frag);
+    total_len.encode(&mut frag);
+    frag.resize(frag.len() + payload_len, 0);
+    assert_eq!(mail_write(getpid() as usize, &frag), frag.len() as isize);
+}
+
+/// 损坏的分片头被拒绝，且不影响之后的正常消息
+fn corrupt_fragments() {
+    let mut mailbox = Mailbox::new();
+    // 声称的长度过大，不能据此分配内存
+    raw_fragment(0, 1, u32::MAX, 8);
+    assert_eq!(mailbox.recv::<Note>().unwrap_err(), MailError::Malformed);
+    // 分片数与长度不符
+    raw_fragment(0, 5, 300, 240);
+    assert_eq!(mailbox.recv::<Note>().unwrap_err(), MailError::Malformed);
+    // 重复的分片
+    raw_fragment(0, 2, 300, 240);
+    raw_fragment(0, 2, 300, 240);
+    assert_eq!(mailbox.recv::<Note>().unwrap_err(), MailError::Malformed);
+    // 缺少第一个分片
+    raw_fragment(1, 2, 300, 60);
+    assert_eq!(mailbox.recv::<Note>().unwrap_err(), MailError::Malformed);
+
+    let big = Blob {
+        seq: 0,
+        data: vec![0; MAX_PAYLOAD_LEN],
+    };
+    let self_pid = getpid() as usize;
+    assert_eq!(mailbox.send(self_pid, &big), Err(MailError::TooLarge));
+    let note = Note(String::from("still works"));
+    mailbox.send(self_pid, &note).unwrap();
+    assert_eq!(mailbox.recv::<Note>().unwrap().msg, note);
+}
+
+fn round_trip<T: Wire + PartialEq + core::fmt::Debug>(value: T) {
+    let mut bytes = Vec::new();
+    value.encode(&mut bytes);
+    let mut input = &bytes[..];
+    assert_eq!(T::decode(&mut input), Some(value));
+    assert!(input.is_empty());
+    // 截断的输入无法解码
+    if !bytes.is_empty() {
+        let mut short = &bytes[..bytes.len() - 1];
+        assert!(T::decode(&mut short).is_none());
+    }
+}
+
+#[no_mangle]
+pub fn main(_argc: usize, argv: &[&str]) -> i32 {
+    logging::parse_args(argv);
+    round_trip(0x1234u16);
+    round_trip(-5i64);
+    round_trip(usize::MAX);
+    round_trip((true, String::from("text"), 9u8));
+    round_trip(blob());
+    corrupt_fragments();
+
+    let server = getpid() as usize;
+    let pid = fork();
+    if pid == 0 {
+        exit(run_client(server));
+    }
+    run_server(pid as usize);
+    let mut code = 0;
+    assert_eq!(waitpid(pid as usize, &mut code), pid);
+    assert_eq!(code, 0);
+
+    let mut mailbox = Mailbox::new();
+    assert_eq!(
+        mailbox.send(usize::MAX, &Shutdown),
+        Err(MailError::NoSuchProcess)
+    );
+    println!("\x1b[32mch7_mailbox_typed 测试通过\x1b[0m");
+    0
+}
@@ -0,0 +1,99 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{
+    close, exec, fork, meminfo, mmap_with, munmap, pipe, waitpid, MapFlags, MemInfo, ProtFlags,
+};
+
+/*
+理想结果：反复进行 fork/exec/exit、mmap/munmap、pipe 创建/关闭之后，
+空闲物理页帧数与本进程驻留页数都回到基线，即内核没有泄漏页帧。
+最终输出 Test mem_leak OK!
+*/
+
+const PAGE_SIZE: usize = 4096;
+const FORK_ROUNDS: usize = 1000;
+const MMAP_ROUNDS: usize = 4000;
+const PIPE_ROUNDS: usize = 4000;
+
+fn snapshot() -> MemInfo {
+    let mut info = MemInfo::new();
+    assert_eq!(meminfo(&mut info), 0);
+    assert!(info.free_frames <= info.total_frames);
+    info
+}
+
+fn fork_exec_exit() {
+    let pid = fork();
+    if pid == 0 {
+        exec("ch5_exit0\0", &[core::ptr::null::<u8>()]);
+        panic!("exec failed");
+    }
+    assert!(pid > 0);
+    let mut exit_code = 0;
+    assert_eq!(waitpid(pid as usize, &mut exit_code), pid);
+    assert_eq!(exit_code, 66778);
+}
+
+fn mmap_munmap() {
+    let len = 4 * PAGE_SIZE;
+    let rw = ProtFlags::READ | ProtFlags::WRITE;
+    let flags = MapFlags::PRIVATE | MapFlags::ANONYMOUS;
+    let addr = mmap_with(0, len, rw, flags, None, 0);
+    assert!(addr > 0);
+    // 逐页写入，确保页帧真正被分配
+    for i in 0..len / PAGE_SIZE {
+        unsafe { *((addr as usize + i * PAGE_SIZE) as *mut u8) = i as u8 };
+    }
+    assert_eq!(munmap(addr as usize, len), 0);
+}
+
+fn pipe_create_close() {
+    let mut fds = [0usize; 2];
+    assert_eq!(pipe(&mut fds), 0);
+    assert_eq!(close(fds[0]), 0);
+    assert_eq!(close(fds[1]), 0);
+}
+
+/// 先执行一轮预热（让内核与用户堆完成一次性的分配），再循环 `rounds` 次并与基线比较
+fn check(name: &str, rounds: usize, f: fn()) {
+    f();
+    let before = snapshot();
+    for _ in 0..rounds {
+        f();
+    }
+    let after = snapshot();
+    println!(
+        "{}: {} rounds, free frames {} -> {}, resident pages {} -> {}",
+        name,
+        rounds,
+        before.free_frames,
+        after.free_frames,
+        before.resident_pages,
+        after.resident_pages
+    );
+    assert_eq!(
+        after.free_frames, before.free_frames,
+        "{} leaks frames",
+        name
+    );
+    assert_eq!(after.resident_pages, before.resident_pages);
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let info = snapshot();
+    println!(
+        "total frames {}, free frames {}, resident pages {}",
+        info.total_frames, info.free_frames, info.resident_pages
+    );
+    assert!(info.resident_pages > 0);
+    check("fork/exec/exit", FORK_ROUNDS, fork_exec_exit);
+    check("mmap/munmap", MMAP_ROUNDS, mmap_munmap);
+    check("pipe", PIPE_ROUNDS, pipe_create_close);
+    println!("Test mem_leak OK!");
+    0
+}
@@ -0,0 +1,88 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{
+    close, get_fd_flags, pipe, pipe2, read, set_nonblocking, write, OpenFlags, EAGAIN, STDIN,
+};
+
+/*
+理想结果：O_NONBLOCK 的管道在无数据时读返回 -EAGAIN、写满时写返回 -EAGAIN，
+写端全部关闭后读返回 0；fcntl 可以切换已有管道与控制台的阻塞模式。
+（运行时不要在控制台输入）
+最终输出 Test nonblock OK!
+*/
+
+const CHUNK: usize = 64;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let mut fds = [0usize; 2];
+    assert_eq!(pipe2(&mut fds, OpenFlags::NONBLOCK), 0);
+    let (rd, wr) = (fds[0], fds[1]);
+    assert!(get_fd_flags(rd).unwrap().contains(OpenFlags::NONBLOCK));
+    assert!(get_fd_flags(wr).unwrap().contains(OpenFlags::NONBLOCK));
+
+    let mut buf = [0u8; CHUNK];
+    assert_eq!(read(rd, &mut buf), -EAGAIN);
+    assert_eq!(write(wr, b"hello"), 5);
+    assert_eq!(read(rd, &mut buf), 5);
+    assert_eq!(&buf[..5], b"hello");
+    assert_eq!(read(rd, &mut buf), -EAGAIN);
+
+    // 写满管道：写入不会阻塞，最后一次写入可能只写入部分数据
+    let mut capacity = 0;
+    loop {
+        match write(wr, &[b'z'; CHUNK]) {
+            n if n > 0 => capacity += n as usize,
+            n => {
+                assert_eq!(n, -EAGAIN);
+                break;
+            }
+        }
+    }
+    assert!(capacity > 0);
+    println!("pipe capacity = {}", capacity);
+    // 读出全部数据
+    let mut drained = 0;
+    loop {
+        match read(rd, &mut buf) {
+            n if n > 0 => {
+                assert!(buf[..n as usize].iter().all(|&b| b == b'z'));
+                drained += n as usize;
+            }
+            n => {
+                assert_eq!(n, -EAGAIN);
+                break;
+            }
+        }
+    }
+    assert_eq!(drained, capacity);
+
+    // 写端关闭后读到 EOF 而不是 EAGAIN
+    close(wr);
+    assert_eq!(read(rd, &mut buf), 0);
+    close(rd);
+
+    // 用 fcntl 切换普通管道
+    assert_eq!(pipe(&mut fds), 0);
+    assert!(!get_fd_flags(fds[0]).unwrap().contains(OpenFlags::NONBLOCK));
+    assert_eq!(set_nonblocking(fds[0], true), 0);
+    assert!(get_fd_flags(fds[0]).unwrap().contains(OpenFlags::NONBLOCK));
+    assert_eq!(read(fds[0], &mut buf), -EAGAIN);
+    assert_eq!(set_nonblocking(fds[0], false), 0);
+    assert!(!get_fd_flags(fds[0]).unwrap().contains(OpenFlags::NONBLOCK));
+    close(fds[0]);
+    close(fds[1]);
+    assert!(get_fd_flags(fds[0]).is_none());
+    assert!(set_nonblocking(fds[0], true) < 0);
+
+    // 控制台：没有输入时立即返回，结束前恢复阻塞模式
+    assert_eq!(set_nonblocking(STDIN, true), 0);
+    assert_eq!(read(STDIN, &mut buf[..1]), -EAGAIN);
+    assert_eq!(set_nonblocking(STDIN, false), 0);
+    println!("Test nonblock OK!");
+    0
+}
@@ -0,0 +1,169 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use core::sync::atomic::{AtomicUsize, Ordering};
+use user_lib::time::Duration;
+use user_lib::{
+    close, exit, fork, mmap_with, munmap, pipe, pipe2, read, sleep, waitpid, write, MapFlags,
+    OpenFlags, ProtFlags, EAGAIN, PIPE_BUF,
+};
+
+/*
+理想结果：管道满时阻塞写入者，直到读者取走数据后才继续；
+多个写入者各自写入 PIPE_BUF 字节的记录时，记录之间不会交错。
+最终输出 Test pipe_capacity OK!
+*/
+
+const PAGE_SIZE: usize = 4096;
+const CHUNK: usize = 64;
+const WRITERS: usize = 4;
+const RECORDS: usize = 32;
+
+/// 用非阻塞写入填满一个新管道，得到管道容量
+fn measure_capacity() -> usize {
+    let mut fds = [0usize; 2];
+    assert_eq!(pipe2(&mut fds, OpenFlags::NONBLOCK), 0);
+    let mut capacity = 0;
+    loop {
+        match write(fds[1], &[0u8; CHUNK]) {
+            n if n > 0 => capacity += n as usize,
+            n => {
+                assert_eq!(n, -EAGAIN);
+                break;
+            }
+        }
+    }
+    close(fds[0]);
+    close(fds[1]);
+    capacity
+}
+
+fn backpressure(capacity: usize) {
+    let total = capacity * 4;
+    let rw = ProtFlags::READ | ProtFlags::WRITE;
+    let flags = MapFlags::SHARED | MapFlags::ANONYMOUS;
+    let addr = mmap_with(0, PAGE_SIZE, rw, flags, None, 0);
+    assert!(addr > 0);
+    // 子进程已经写入的字节数
+    let progress = unsafe { &*(addr as *const AtomicUsize) };
+    progress.store(0, Ordering::SeqCst);
+
+    let mut fds = [0usize; 2];
+    assert_eq!(pipe(&mut fds), 0);
+    let pid = fork();
+    if pid == 0 {
+        close(fds[0]);
+        let mut written = 0;
+        while written < total {
+            let len = CHUNK.min(total - written);
+            let mut chunk = [0u8; CHUNK];
+            for (i, b) in chunk[..len].iter_mut().enumerate() {
+                *b = (written + i) as u8;
+            }
+            let n = write(fds[1], &chunk[..len]);
+            assert!(n > 0);
+            written += n as usize;
+            progress.store(written, Ordering::SeqCst);
+        }
+        exit(0);
+    }
+    close(fds[1]);
+    sleep(Duration::from_millis(100)).unwrap();
+    // 没有读者取数据时，写入者最多写满管道
+    let blocked_at = progress.load(Ordering::SeqCst);
+    assert!(blocked_at > 0 && blocked_at <= capacity, "{}", blocked_at);
+
+    let mut buf = [0u8; CHUNK];
+    let mut received = 0;
+    loop {
+        let n = read(fds[0], &mut buf);
+        assert!(n >= 0);
+        if n == 0 {
+            break;
+        }
+        for (i, &b) in buf[..n as usize].iter().enumerate() {
+            assert_eq!(b, (received + i) as u8);
+        }
+        received += n as usize;
+    }
+    assert_eq!(received, total);
+    assert_eq!(progress.load(Ordering::SeqCst), total);
+    close(fds[0]);
+    let mut exit_code = 0;
+    assert_eq!(waitpid(pid as usize, &mut exit_code), pid);
+    assert_eq!(exit_code, 0);
+    munmap(addr as usize, PAGE_SIZE);
+}
+
+/// 记录：第 0 字节为写入者编号，第 1 字节为序号，其余字节为编号与序号之和
+fn record(writer: usize, seq: usize) -> [u8; PIPE_BUF] {
+    let mut rec = [(writer + seq) as u8; PIPE_BUF];
+    rec[0] = writer as u8;
+    rec[1] = seq as u8;
+    rec
+}
+
+fn atomic_writes() {
+    let mut fds = [0usize; 2];
+    assert_eq!(pipe(&mut fds), 0);
+    let mut pids = [0isize; WRITERS];
+    for (writer, pid) in pids.iter_mut().enumerate() {
+        *pid = fork();
+        if *pid == 0 {
+            close(fds[0]);
+            for seq in 0..RECORDS {
+                assert_eq!(write(fds[1], &record(writer, seq)), PIPE_BUF as isize);
+            }
+            exit(0);
+        }
+    }
+    close(fds[1]);
+
+    let mut next_seq = [0usize; WRITERS];
+    let mut rec = [0u8; PIPE_BUF];
+    let mut filled = 0;
+    loop {
+        let n = read(fds[0], &mut rec[filled..]);
+        assert!(n >= 0);
+        if n == 0 {
+            break;
+        }
+        filled += n as usize;
+        if filled < PIPE_BUF {
+            continue;
+        }
+        filled = 0;
+        let writer = rec[0] as usize;
+        assert!(writer < WRITERS);
+        let seq = next_seq[writer];
+        assert_eq!(
+            rec[1] as usize, seq,
+            "records of writer {} reordered",
+            writer
+        );
+        assert!(rec[..] == record(writer, seq)[..], "interleaved record");
+        next_seq[writer] += 1;
+    }
+    assert_eq!(filled, 0);
+    assert!(next_seq.iter().all(|&n| n == RECORDS));
+    close(fds[0]);
+    for &pid in pids.iter() {
+        let mut exit_code = 0;
+        assert_eq!(waitpid(pid as usize, &mut exit_code), pid);
+        assert_eq!(exit_code, 0);
+    }
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let capacity = measure_capacity();
+    println!("pipe capacity = {}", capacity);
+    assert!(capacity >= PIPE_BUF);
+    backpressure(capacity);
+    atomic_writes();
+    println!("Test pipe_capacity OK!");
+    0
+}
@@ -0,0 +1,118 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use core::sync::atomic::{AtomicBool, Ordering};
+use user_lib::time::Duration;
+use user_lib::{
+    close, dup, exit, fork, pipe, pipe2, read, sigaction, sigprocmask, sigreturn, sleep, waitpid,
+    write, OpenFlags, SignalAction, SignalFlags, EAGAIN, EPIPE, SIGPIPE,
+};
+
+/*
+理想结果：读端在所有写端（包括 dup 出来的和子进程继承的）都关闭后才读到 EOF；
+向没有读者的管道写入时，默认会被 SIGPIPE 杀死或写入失败，
+屏蔽 SIGPIPE 时返回 -EPIPE，安装处理函数时处理函数被调用且写入返回 -EPIPE。
+最终输出 Test pipe_close OK!
+*/
+
+/// 子进程写入失败（而不是被信号杀死）时的退出码
+const WRITE_FAILED: i32 = 1;
+
+static HANDLED: AtomicBool = AtomicBool::new(false);
+
+fn on_sigpipe() {
+    HANDLED.store(true, Ordering::SeqCst);
+    sigreturn();
+}
+
+fn eof_after_all_writers() {
+    let mut fds = [0usize; 2];
+    assert_eq!(pipe2(&mut fds, OpenFlags::NONBLOCK), 0);
+    let (rd, wr) = (fds[0], fds[1]);
+    let wr_dup = dup(wr);
+    assert!(wr_dup > 0);
+    let wr_dup = wr_dup as usize;
+
+    // 子进程继承写端，稍后写入一个字节再退出
+    let pid = fork();
+    if pid == 0 {
+        close(rd);
+        close(wr_dup);
+        sleep(Duration::from_millis(50)).unwrap();
+        assert_eq!(write(wr, b"c"), 1);
+        exit(0);
+    }
+    let mut buf = [0u8; 4];
+    close(wr);
+    // 仍有 dup 出来的写端与子进程的写端
+    assert_eq!(read(rd, &mut buf), -EAGAIN);
+    close(wr_dup);
+    // 仅剩子进程的写端
+    assert_eq!(read(rd, &mut buf), -EAGAIN);
+    let mut exit_code = 0;
+    assert_eq!(waitpid(pid as usize, &mut exit_code), pid);
+    assert_eq!(exit_code, 0);
+    // 子进程退出时关闭了最后一个写端，先读到数据再读到 EOF
+    assert_eq!(read(rd, &mut buf), 1);
+    assert_eq!(buf[0], b'c');
+    assert_eq!(read(rd, &mut buf), 0);
+    assert_eq!(read(rd, &mut buf), 0);
+    close(rd);
+}
+
+/// 在子进程中关闭读端后运行 `f(写端)`，返回子进程退出码
+fn without_reader(f: fn(usize) -> i32) -> i32 {
+    let mut fds = [0usize; 2];
+    assert_eq!(pipe(&mut fds), 0);
+    let pid = fork();
+    if pid == 0 {
+        close(fds[0]);
+        exit(f(fds[1]));
+    }
+    close(fds[0]);
+    close(fds[1]);
+    let mut exit_code = 0;
+    assert_eq!(waitpid(pid as usize, &mut exit_code), pid);
+    exit_code
+}
+
+fn default_action(wr: usize) -> i32 {
+    // 被 SIGPIPE 杀死时不会返回
+    if write(wr, b"x") < 0 {
+        WRITE_FAILED
+    } else {
+        0
+    }
+}
+
+fn masked(wr: usize) -> i32 {
+    assert!(sigprocmask(SignalFlags::SIGPIPE.bits() as u32) >= 0);
+    assert_eq!(write(wr, b"x"), -EPIPE);
+    assert_eq!(write(wr, b"x"), -EPIPE);
+    0
+}
+
+fn handled(wr: usize) -> i32 {
+    let action = SignalAction {
+        handler: on_sigpipe as usize,
+        ..Default::default()
+    };
+    assert_eq!(sigaction(SIGPIPE, Some(&action), None), 0);
+    assert_eq!(write(wr, b"x"), -EPIPE);
+    assert!(HANDLED.load(Ordering::SeqCst));
+    0
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    eof_after_all_writers();
+    // 写入成功则返回 0，这是唯一不允许的结果
+    assert_ne!(without_reader(default_action), 0);
+    assert_eq!(without_reader(masked), 0);
+    assert_eq!(without_reader(handled), 0);
+    println!("Test pipe_close OK!");
+    0
+}
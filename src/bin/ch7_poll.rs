@@ -0,0 +1,184 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::time::{Duration, Instant};
+use user_lib::{
+    close, exit, fork, getpid, mail_read, mail_write, mailbox_fd, pipe, poll, ppoll, read, sleep,
+    waitpid, write, PollEvents, PollFd, SignalFlags, STDIN,
+};
+
+/*
+理想结果：一个进程用 poll 同时等待多个管道和控制台，
+按子进程写入的先后顺序收到消息，不会阻塞在任何一个数据源上；
+写端关闭后得到 HUP，关闭的 fd 得到 NVAL，超时按时返回；
+邮箱 fd 在有邮件时可读。
+（运行时不要在控制台输入）
+最终输出 Test poll OK!
+*/
+
+const WRITERS: usize = 3;
+/// 各子进程写入前的延迟，故意与创建顺序相反
+const DELAYS_MS: [u64; WRITERS] = [150, 100, 50];
+const MESSAGES: usize = 2;
+
+fn writer(id: usize, fd: usize) -> ! {
+    for _ in 0..MESSAGES {
+        sleep(Duration::from_millis(DELAYS_MS[id])).unwrap();
+        assert_eq!(write(fd, &[b'0' + id as u8]), 1);
+    }
+    close(fd);
+    exit(0);
+}
+
+fn multiplex() {
+    let mut pids = [0isize; WRITERS];
+    // fds[0] 为控制台，其余为各子进程的管道读端
+    let mut fds = [PollFd::new(STDIN, PollEvents::IN); WRITERS + 1];
+    for id in 0..WRITERS {
+        let mut pipe_fd = [0usize; 2];
+        assert_eq!(pipe(&mut pipe_fd), 0);
+        pids[id] = fork();
+        if pids[id] == 0 {
+            close(pipe_fd[0]);
+            writer(id, pipe_fd[1]);
+        }
+        close(pipe_fd[1]);
+        fds[id + 1] = PollFd::new(pipe_fd[0], PollEvents::IN);
+    }
+
+    let mut order = [0u8; WRITERS * MESSAGES];
+    let mut received = 0;
+    let mut open = WRITERS;
+    while open > 0 {
+        let ready = poll(&mut fds, 5000);
+        assert!(ready > 0, "poll timed out");
+        assert!(fds[0].revents.is_empty(), "unexpected console input");
+        for pfd in fds[1..].iter_mut() {
+            if pfd.revents.contains(PollEvents::IN) {
+                let mut buf = [0u8; 8];
+                let n = read(pfd.fd as usize, &mut buf);
+                assert!(n >= 0);
+                for &c in buf[..n as usize].iter() {
+                    order[received] = c;
+                    received += 1;
+                }
+                if n > 0 {
+                    continue;
+                }
+            }
+            if pfd.revents.intersects(PollEvents::IN | PollEvents::HUP) {
+                // 写端已关闭，不再等待这个 fd
+                close(pfd.fd as usize);
+                pfd.fd = -1;
+                open -= 1;
+            }
+        }
+    }
+    for &pid in pids.iter() {
+        let mut exit_code = 0;
+        assert_eq!(waitpid(pid as usize, &mut exit_code), pid);
+        assert_eq!(exit_code, 0);
+    }
+    assert_eq!(received, WRITERS * MESSAGES);
+    // 消息时刻：子进程 2 为 50/100ms，1 为 100/200ms，0 为 150/300ms
+    let count = |c: u8| order.iter().filter(|&&x| x == c).count();
+    assert!((0..WRITERS).all(|id| count(b'0' + id as u8) == MESSAGES));
+    assert_eq!(order[0], b'2');
+    assert_eq!(order[WRITERS * MESSAGES - 1], b'0');
+}
+
+fn timeouts() {
+    let mut pipe_fd = [0usize; 2];
+    assert_eq!(pipe(&mut pipe_fd), 0);
+    let mut fds = [
+        PollFd::new(pipe_fd[0], PollEvents::IN),
+        PollFd::new(STDIN, PollEvents::IN),
+    ];
+    // 超时为 0 时立即返回
+    assert_eq!(poll(&mut fds, 0), 0);
+    let start = Instant::now();
+    assert_eq!(poll(&mut fds, 50), 0);
+    assert!(start.elapsed() >= Duration::from_millis(50));
+    let start = Instant::now();
+    let mask = Some(SignalFlags::SIGUSR1);
+    assert_eq!(ppoll(&mut fds, Some(Duration::from_millis(20)), mask), 0);
+    assert!(start.elapsed() >= Duration::from_millis(20));
+
+    // 写端随时可写
+    let mut out = [PollFd::new(pipe_fd[1], PollEvents::OUT)];
+    assert_eq!(poll(&mut out, -1), 1);
+    assert!(out[0].revents.contains(PollEvents::OUT));
+
+    // 写入数据后读端就绪
+    assert_eq!(write(pipe_fd[1], b"x"), 1);
+    assert_eq!(poll(&mut fds, -1), 1);
+    assert!(fds[0].revents.contains(PollEvents::IN));
+    assert!(fds[1].revents.is_empty());
+
+    // 读端全部关闭后写端报告 ERR
+    close(pipe_fd[0]);
+    assert_eq!(poll(&mut out, 0), 1);
+    assert!(out[0].revents.contains(PollEvents::ERR));
+
+    // 已关闭的 fd 报告 NVAL，负数 fd 被忽略
+    let mut fds = [
+        PollFd::new(pipe_fd[0], PollEvents::IN),
+        PollFd {
+            fd: -1,
+            events: PollEvents::IN,
+            revents: PollEvents::empty(),
+        },
+    ];
+    assert_eq!(poll(&mut fds, 0), 1);
+    assert!(fds[0].revents.contains(PollEvents::NVAL));
+    assert!(fds[1].revents.is_empty());
+    close(pipe_fd[1]);
+}
+
+fn mailbox() {
+    let mbox = mailbox_fd();
+    assert!(mbox >= 0);
+    let mut pipe_fd = [0usize; 2];
+    assert_eq!(pipe(&mut pipe_fd), 0);
+    let mut fds = [
+        PollFd::new(pipe_fd[0], PollEvents::IN),
+        PollFd::new(mbox as usize, PollEvents::IN),
+    ];
+    assert_eq!(poll(&mut fds, 0), 0);
+
+    let parent = getpid() as usize;
+    let pid = fork();
+    if pid == 0 {
+        sleep(Duration::from_millis(50)).unwrap();
+        assert_eq!(mail_write(parent, b"mail"), 4);
+        exit(0);
+    }
+    // 只有邮箱就绪，管道仍为空
+    assert_eq!(poll(&mut fds, 5000), 1);
+    assert!(fds[0].revents.is_empty());
+    assert!(fds[1].revents.contains(PollEvents::IN));
+    let mut buf = [0u8; 8];
+    assert_eq!(mail_read(&mut buf), 4);
+    assert_eq!(&buf[..4], b"mail");
+    // 邮件取走后不再就绪
+    assert_eq!(poll(&mut fds, 0), 0);
+
+    let mut exit_code = 0;
+    assert_eq!(waitpid(pid as usize, &mut exit_code), pid);
+    assert_eq!(exit_code, 0);
+    close(mbox as usize);
+    close(pipe_fd[0]);
+    close(pipe_fd[1]);
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    multiplex();
+    timeouts();
+    mailbox();
+    println!("Test poll OK!");
+    0
+}
@@ -0,0 +1,85 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::readline::Editor;
+use user_lib::{close, dup, exit, fork, pipe, waitpid, write, STDIN};
+
+/// 正确输出：（无报错信息）
+/// Test readline OK!
+
+/// 按键序列及每行期望的编辑结果
+const SCRIPT: &[(&[u8], &str)] = &[
+    // 普通输入与退格
+    (b"helo\x7flo\r", "hello"),
+    // 左移后插入，Home/End（CSI 与 SS3 两种形式）
+    (
+        b"wrld\x1b[D\x1b[D\x1b[Do\x1b[Hhello \x1bOF!\n",
+        "hello world!",
+    ),
+    // Ctrl-A / Ctrl-E 与 Delete 键
+    (b"xabc\x01\x1b[3~\x05d\r", "abcd"),
+    // Ctrl-W 删除前一个单词（包括其后的空格）
+    (b"one two  three\x17\x17four\r", "one four"),
+    // Ctrl-U 删除光标之前的全部内容，Ctrl-K 删除之后的
+    (
+        b"junkkeepjunk\x01\x1b[C\x1b[C\x1b[C\x1b[C\x15\x1b[C\x1b[C\x1b[C\x1b[C\x0b\r",
+        "keep",
+    ),
+    // 上箭头取回上一条历史，再按两次后回到更早的条目
+    (b"\x1b[A\r", "keep"),
+    (b"\x1b[A\x1b[A\r", "one four"),
+    // 浏览历史后按下箭头回到正在编辑的行
+    (b"draft\x1b[A\x1b[A\x1b[B\x1b[B\r", "draft"),
+    // 空行不进入历史
+    (b"\r", ""),
+];
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let mut pipe_fd = [0usize; 2];
+    assert_eq!(pipe(&mut pipe_fd), 0);
+    let pid = fork();
+    if pid == 0 {
+        close(pipe_fd[0]);
+        for (keys, _) in SCRIPT.iter() {
+            assert_eq!(write(pipe_fd[1], keys), keys.len() as isize);
+        }
+        close(pipe_fd[1]);
+        exit(0);
+    }
+    close(pipe_fd[1]);
+    close(STDIN);
+    assert_eq!(dup(pipe_fd[0]), STDIN as isize);
+    close(pipe_fd[0]);
+
+    let mut editor = Editor::new(3);
+    for (_, expected) in SCRIPT.iter() {
+        let line = editor.readline("> ").expect("unexpected end of input");
+        assert_eq!(line.as_str(), *expected);
+    }
+    // 写端全部关闭后读到 EOF
+    assert!(editor.readline("> ").is_none());
+
+    // 历史有界，最旧的条目被丢弃，重复的相邻条目只记录一次
+    {
+        let mut history = editor.history();
+        assert_eq!(history.next(), Some("keep"));
+        assert_eq!(history.next(), Some("one four"));
+        assert_eq!(history.next(), Some("draft"));
+        assert_eq!(history.next(), None);
+    }
+
+    // 也可以从任意字节源读取
+    let mut keys = b"ab\x1b[Dc\r".iter().copied();
+    let line = editor.readline_with("> ", || keys.next());
+    assert_eq!(line.as_deref(), Some("acb"));
+
+    let mut exit_code = 0;
+    assert_eq!(waitpid(pid as usize, &mut exit_code), pid);
+    assert_eq!(exit_code, 0);
+    println!("Test readline OK!");
+    0
+}
@@ -0,0 +1,48 @@
+#![no_std]
+#![no_main]
+
+extern crate user_lib;
+
+use user_lib::time::{Duration, Instant};
+use user_lib::*;
+
+/// 正确输出：（无报错信息）
+/// Test sleep_interrupt OK!
+
+fn func() {
+    sigreturn();
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let full = Duration::from_millis(1000);
+    let pid = fork();
+    if pid == 0 {
+        let new = SignalAction {
+            handler: func as usize,
+            ..Default::default()
+        };
+        if sigaction(SIGUSR1, Some(&new), None) < 0 {
+            panic!("Sigaction failed!");
+        }
+        let start = Instant::now();
+        let rem = sleep(full).unwrap();
+        let slept = start.elapsed();
+        println!(
+            "child slept {}ms, {}ms remaining",
+            slept.as_millis(),
+            rem.as_millis()
+        );
+        // 被信号打断：剩余时间非零，且与实际睡眠时间之和不小于请求时长
+        assert!(rem > Duration::ZERO && rem < full);
+        assert!(slept + rem >= full);
+        exit(0);
+    }
+    sleep(Duration::from_millis(200)).unwrap();
+    assert!(kill(pid as usize, SIGUSR1) >= 0);
+    let mut exit_code = 0;
+    assert_eq!(waitpid(pid as usize, &mut exit_code), pid);
+    assert_eq!(exit_code, 0);
+    println!("Test sleep_interrupt OK!");
+    0
+}
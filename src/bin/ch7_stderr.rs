@@ -0,0 +1,139 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{
+    close, dup, exit, flush, fork, pipe, read, set_buffer_mode, set_panic_to_stderr, waitpid,
+    BufferMode, STDERR, STDOUT,
+};
+
+/// 正确输出：（无报错信息）
+/// Test stderr OK!
+
+/// 在子进程中把 stdout 和/或 stderr 重定向到管道后运行 `f`，返回管道中读到的全部内容
+fn capture(redirect_out: bool, redirect_err: bool, f: fn(), buf: &mut [u8]) -> usize {
+    let mut pipe_fd = [0usize; 2];
+    assert_eq!(pipe(&mut pipe_fd), 0);
+    let pid = fork();
+    if pid == 0 {
+        close(pipe_fd[0]);
+        if redirect_out {
+            close(STDOUT);
+            assert_eq!(dup(pipe_fd[1]), STDOUT as isize);
+        }
+        if redirect_err {
+            close(STDERR);
+            assert_eq!(dup(pipe_fd[1]), STDERR as isize);
+        }
+        close(pipe_fd[1]);
+        f();
+        exit(0);
+    }
+    close(pipe_fd[1]);
+    let mut len = 0;
+    loop {
+        match read(pipe_fd[0], &mut buf[len..]) {
+            n if n <= 0 => break,
+            n => len += n as usize,
+        }
+    }
+    close(pipe_fd[0]);
+    let mut exit_code = 0;
+    assert_eq!(waitpid(pid as usize, &mut exit_code), pid);
+    assert_eq!(exit_code, 0);
+    len
+}
+
+fn out_and_err() {
+    println!("to stdout");
+    eprintln!("to stderr");
+}
+
+fn line_buffered() {
+    set_buffer_mode(BufferMode::Line);
+    print!("o");
+    eprint!("e");
+    println!("o");
+}
+
+fn unbuffered() {
+    set_buffer_mode(BufferMode::Unbuffered);
+    print!("o");
+    eprint!("e");
+    println!("o");
+}
+
+fn fully_buffered() {
+    set_buffer_mode(BufferMode::Full);
+    println!("out1");
+    eprintln!("err1");
+    println!("out2");
+    flush();
+    eprintln!("err2");
+}
+
+/// 子进程开启 set_panic_to_stderr 后 panic，stderr 重定向到管道；返回读到的开头部分
+fn capture_panic(buf: &mut [u8]) -> usize {
+    let mut pipe_fd = [0usize; 2];
+    assert_eq!(pipe(&mut pipe_fd), 0);
+    let pid = fork();
+    if pid == 0 {
+        close(pipe_fd[0]);
+        close(STDERR);
+        assert_eq!(dup(pipe_fd[1]), STDERR as isize);
+        close(pipe_fd[1]);
+        set_panic_to_stderr(true);
+        panic!("to stderr");
+    }
+    close(pipe_fd[1]);
+    let mut len = 0;
+    let mut rest = [0u8; 64];
+    loop {
+        // 只保留开头，其余的回溯信息读出后丢弃
+        let n = if len < buf.len() {
+            read(pipe_fd[0], &mut buf[len..])
+        } else {
+            read(pipe_fd[0], &mut rest)
+        };
+        if n <= 0 {
+            break;
+        }
+        len = (len + n as usize).min(buf.len());
+    }
+    close(pipe_fd[0]);
+    let mut exit_code = 0;
+    assert_eq!(waitpid(pid as usize, &mut exit_code), pid);
+    assert_eq!(exit_code, -1);
+    len
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let mut buf = [0u8; 64];
+
+    // 只重定向 stderr：管道里只有诊断信息
+    let len = capture(false, true, out_and_err, &mut buf);
+    assert_eq!(&buf[..len], b"to stderr\n");
+    // 只重定向 stdout：管道里只有程序输出
+    let len = capture(true, false, out_and_err, &mut buf);
+    assert_eq!(&buf[..len], b"to stdout\n");
+
+    // 两者指向同一管道时，写入顺序反映缓冲模式
+    let len = capture(true, true, line_buffered, &mut buf);
+    assert_eq!(&buf[..len], b"eoo\n");
+    let len = capture(true, true, unbuffered, &mut buf);
+    assert_eq!(&buf[..len], b"oeo\n");
+    let len = capture(true, true, fully_buffered, &mut buf);
+    assert_eq!(&buf[..len], b"err1\nout1\nout2\nerr2\n");
+
+    // 选择后 panic 信息写到 stderr
+    let len = capture_panic(&mut buf);
+    let text = core::str::from_utf8(&buf[..len]).unwrap_or("");
+    assert!(text.starts_with("Panicked at"), "{}", text);
+    assert!(text.contains("to stderr"), "{}", text);
+
+    println!("Test stderr OK!");
+    0
+}
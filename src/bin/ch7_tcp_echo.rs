@@ -0,0 +1,126 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::net::{
+    accept_from, bind, connect, getpeername, getsockname, listen, recv, send, shutdown, socket,
+    Ipv4Addr, MsgFlags, Shutdown, SockAddrIn, AF_INET, SOCK_STREAM,
+};
+use user_lib::{close, exit, fork, waitpid};
+
+/*
+理想结果：在 127.0.0.1 上运行 TCP 回显服务器，客户端进程发送的数据按顺序完整返回；
+accept/getpeername/getsockname 报告正确的回环地址与端口；
+端口被占用时 bind 失败，连接未监听的端口失败。不需要外部网络。
+最终输出 Test tcp_echo OK!
+*/
+
+const PORT: u16 = 6200;
+const CLOSED_PORT: u16 = 6209;
+const TOTAL: usize = 4000;
+const CHUNK: usize = 128;
+
+fn tcp_socket() -> usize {
+    let fd = socket(AF_INET, SOCK_STREAM);
+    assert!(fd > 0);
+    fd as usize
+}
+
+fn byte_at(i: usize) -> u8 {
+    (i * 7 + i / 256) as u8
+}
+
+fn run_client() -> i32 {
+    let server = SockAddrIn::new(Ipv4Addr::LOCALHOST, PORT);
+    let fd = tcp_socket();
+    assert_eq!(connect(fd, &server), 0);
+    let mut peer = SockAddrIn::new(Ipv4Addr::UNSPECIFIED, 0);
+    assert_eq!(getpeername(fd, &mut peer), 0);
+    assert_eq!(peer, server);
+
+    let mut sent = 0;
+    let mut received = 0;
+    let mut buf = [0u8; CHUNK];
+    while received < TOTAL {
+        if sent < TOTAL {
+            let len = CHUNK.min(TOTAL - sent);
+            for (i, b) in buf[..len].iter_mut().enumerate() {
+                *b = byte_at(sent + i);
+            }
+            let n = send(fd, &buf[..len], MsgFlags::empty());
+            assert!(n > 0);
+            sent += n as usize;
+        }
+        // TCP 不保留消息边界，一次可能读到多个或半个块
+        let n = recv(fd, &mut buf, MsgFlags::empty());
+        assert!(n > 0, "connection closed early");
+        for (i, &b) in buf[..n as usize].iter().enumerate() {
+            assert_eq!(b, byte_at(received + i), "offset {}", received + i);
+        }
+        received += n as usize;
+    }
+    assert_eq!(shutdown(fd, Shutdown::Write), 0);
+    assert_eq!(recv(fd, &mut buf, MsgFlags::empty()), 0);
+    close(fd);
+    0
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let listener = tcp_socket();
+    let local = SockAddrIn::new(Ipv4Addr::LOCALHOST, PORT);
+    assert_eq!(bind(listener, &local), 0);
+    assert_eq!(listen(listener, 1), 0);
+    let mut name = SockAddrIn::new(Ipv4Addr::UNSPECIFIED, 0);
+    assert_eq!(getsockname(listener, &mut name), 0);
+    assert_eq!(name, local);
+    // 端口已被占用
+    let other = tcp_socket();
+    assert!(bind(other, &local) < 0);
+    close(other);
+
+    let pid = fork();
+    if pid == 0 {
+        close(listener);
+        exit(run_client());
+    }
+    let mut peer = SockAddrIn::new(Ipv4Addr::UNSPECIFIED, 0);
+    let conn = accept_from(listener, &mut peer);
+    assert!(conn > 0);
+    let conn = conn as usize;
+    assert!(peer.ip().is_loopback());
+    assert_ne!(peer.port(), 0);
+    assert_ne!(peer.port(), PORT);
+
+    let mut buf = [0u8; CHUNK];
+    let mut total = 0;
+    loop {
+        let n = recv(conn, &mut buf, MsgFlags::empty());
+        assert!(n >= 0);
+        if n == 0 {
+            break;
+        }
+        let mut off = 0;
+        while off < n as usize {
+            let m = send(conn, &buf[off..n as usize], MsgFlags::empty());
+            assert!(m > 0);
+            off += m as usize;
+        }
+        total += n as usize;
+    }
+    assert_eq!(total, TOTAL);
+    close(conn);
+    let mut exit_code = 0;
+    assert_eq!(waitpid(pid as usize, &mut exit_code), pid);
+    assert_eq!(exit_code, 0);
+    close(listener);
+
+    // 没有监听者的端口
+    let fd = tcp_socket();
+    assert!(connect(fd, &SockAddrIn::new(Ipv4Addr::LOCALHOST, CLOSED_PORT)) < 0);
+    close(fd);
+    println!("Test tcp_echo OK!");
+    0
+}
@@ -0,0 +1,104 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::net::{
+    bind, getsockname, recvfrom, sendto, socket, Ipv4Addr, MsgFlags, SockAddrIn, AF_INET,
+    SOCK_DGRAM,
+};
+use user_lib::{close, exit, fork, waitpid};
+
+/*
+理想结果：两个进程在 127.0.0.1 上用 UDP 互相发送 ping/pong，
+每条回复带有递增的序号，recvfrom 报告正确的发送者地址；
+绑定端口 0 时由内核分配端口。不需要外部网络。
+最终输出 Test udp_pingpong OK!
+*/
+
+const SERVER_PORT: u16 = 6201;
+const ROUNDS: u32 = 10;
+
+fn udp_socket() -> usize {
+    let fd = socket(AF_INET, SOCK_DGRAM);
+    assert!(fd > 0);
+    fd as usize
+}
+
+/// 消息格式：4 字节标记 + 4 字节小端序号
+fn message(tag: &[u8; 4], seq: u32) -> [u8; 8] {
+    let mut msg = [0u8; 8];
+    msg[..4].copy_from_slice(tag);
+    msg[4..].copy_from_slice(&seq.to_le_bytes());
+    msg
+}
+
+fn parse(msg: &[u8], tag: &[u8; 4]) -> u32 {
+    assert_eq!(msg.len(), 8);
+    assert_eq!(&msg[..4], tag);
+    let mut seq = [0u8; 4];
+    seq.copy_from_slice(&msg[4..]);
+    u32::from_le_bytes(seq)
+}
+
+fn run_client() -> i32 {
+    let server = SockAddrIn::new(Ipv4Addr::LOCALHOST, SERVER_PORT);
+    let fd = udp_socket();
+    assert_eq!(bind(fd, &SockAddrIn::new(Ipv4Addr::LOCALHOST, 0)), 0);
+    let mut local = SockAddrIn::new(Ipv4Addr::UNSPECIFIED, 0);
+    assert_eq!(getsockname(fd, &mut local), 0);
+    assert_ne!(local.port(), 0);
+
+    let mut buf = [0u8; 16];
+    let mut from = SockAddrIn::new(Ipv4Addr::UNSPECIFIED, 0);
+    for seq in 0..ROUNDS {
+        let ping = message(b"PING", seq);
+        assert_eq!(sendto(fd, &ping, MsgFlags::empty(), &server), 8);
+        let n = recvfrom(fd, &mut buf, MsgFlags::empty(), &mut from);
+        assert_eq!(parse(&buf[..n as usize], b"PONG"), seq + 1);
+        assert_eq!(from, server);
+    }
+    let bye = message(b"DONE", ROUNDS);
+    assert_eq!(sendto(fd, &bye, MsgFlags::empty(), &server), 8);
+    close(fd);
+    0
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let fd = udp_socket();
+    assert_eq!(
+        bind(fd, &SockAddrIn::new(Ipv4Addr::LOCALHOST, SERVER_PORT)),
+        0
+    );
+    let pid = fork();
+    if pid == 0 {
+        close(fd);
+        exit(run_client());
+    }
+
+    let mut buf = [0u8; 16];
+    let mut from = SockAddrIn::new(Ipv4Addr::UNSPECIFIED, 0);
+    let mut client_port = None;
+    for seq in 0..ROUNDS {
+        let n = recvfrom(fd, &mut buf, MsgFlags::empty(), &mut from);
+        assert_eq!(parse(&buf[..n as usize], b"PING"), seq);
+        assert!(from.ip().is_loopback());
+        // 所有 ping 都来自同一个端口
+        assert_eq!(*client_port.get_or_insert(from.port()), from.port());
+        let pong = message(b"PONG", seq + 1);
+        assert_eq!(sendto(fd, &pong, MsgFlags::empty(), &from), 8);
+    }
+    let n = recvfrom(fd, &mut buf, MsgFlags::empty(), &mut from);
+    assert_eq!(parse(&buf[..n as usize], b"DONE"), ROUNDS);
+    // 没有数据时不阻塞
+    assert!(recvfrom(fd, &mut buf, MsgFlags::DONTWAIT, &mut from) < 0);
+
+    let mut exit_code = 0;
+    assert_eq!(waitpid(pid as usize, &mut exit_code), pid);
+    assert_eq!(exit_code, 0);
+    close(fd);
+    println!("Test udp_pingpong OK!");
+    0
+}
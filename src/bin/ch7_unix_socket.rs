@@ -0,0 +1,199 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::net::{
+    accept, accept_from, bind, connect, listen, recv, recvfrom, send, sendto, shutdown, socket,
+    MsgFlags, Shutdown, SockAddrUn, AF_UNIX, SOCK_DGRAM, SOCK_STREAM,
+};
+use user_lib::{close, exit, fork, unlink, waitpid, EPIPE};
+
+/*
+理想结果：回显服务器与多个客户端进程通过 AF_UNIX 流套接字通信，
+每个客户端收到的回显按顺序且完整；对端关闭后读到 EOF、写入返回 -EPIPE；
+数据报套接字保留消息边界并报告发送者地址。
+最终输出 Test unix_socket OK!
+*/
+
+const SERVER_PATH: &str = "unix_echo_sock\0";
+const CLIENTS: usize = 3;
+const CHUNKS: usize = 40;
+const CHUNK: usize = 100;
+
+fn stream_socket() -> usize {
+    let fd = socket(AF_UNIX, SOCK_STREAM);
+    assert!(fd > 0);
+    fd as usize
+}
+
+fn chunk(client: usize, seq: usize) -> [u8; CHUNK] {
+    let mut buf = [0u8; CHUNK];
+    for (i, b) in buf.iter_mut().enumerate() {
+        *b = (client * 64 + seq + i) as u8;
+    }
+    buf
+}
+
+/// 读满 `buf`，对端提前关闭时返回已读到的字节数
+fn recv_exact(fd: usize, buf: &mut [u8]) -> usize {
+    let mut got = 0;
+    while got < buf.len() {
+        let n = recv(fd, &mut buf[got..], MsgFlags::empty());
+        assert!(n >= 0);
+        if n == 0 {
+            break;
+        }
+        got += n as usize;
+    }
+    got
+}
+
+fn run_client(id: usize) -> i32 {
+    let addr = SockAddrUn::new(SERVER_PATH).unwrap();
+    let fd = stream_socket();
+    assert_eq!(connect(fd, &addr), 0);
+    let mut echo = [0u8; CHUNK];
+    for seq in 0..CHUNKS {
+        let out = chunk(id, seq);
+        assert_eq!(send(fd, &out, MsgFlags::empty()), CHUNK as isize);
+        assert_eq!(recv_exact(fd, &mut echo), CHUNK);
+        assert_eq!(echo, out, "client {} chunk {}", id, seq);
+    }
+    // 关闭写方向，服务器读到 EOF 后关闭连接，客户端随后读到 EOF
+    assert_eq!(shutdown(fd, Shutdown::Write), 0);
+    assert_eq!(recv(fd, &mut echo, MsgFlags::empty()), 0);
+    close(fd);
+    0
+}
+
+/// 逐个处理连接，把收到的数据原样发回，直到客户端关闭写方向
+fn serve(listener: usize, connections: usize) {
+    let mut buf = [0u8; 64];
+    for _ in 0..connections {
+        let conn = accept(listener);
+        assert!(conn > 0);
+        let conn = conn as usize;
+        let mut total = 0;
+        loop {
+            let n = recv(conn, &mut buf, MsgFlags::empty());
+            assert!(n >= 0);
+            if n == 0 {
+                break;
+            }
+            assert_eq!(send(conn, &buf[..n as usize], MsgFlags::empty()), n);
+            total += n as usize;
+        }
+        assert_eq!(total, CHUNKS * CHUNK);
+        close(conn);
+    }
+}
+
+fn echo_server() {
+    unlink(SERVER_PATH);
+    let addr = SockAddrUn::new(SERVER_PATH).unwrap();
+    let listener = stream_socket();
+    assert_eq!(bind(listener, &addr), 0);
+    assert_eq!(listen(listener, CLIENTS), 0);
+    // 路径已被占用
+    let other = stream_socket();
+    assert!(bind(other, &addr) < 0);
+    close(other);
+
+    let mut pids = [0isize; CLIENTS];
+    for (id, pid) in pids.iter_mut().enumerate() {
+        *pid = fork();
+        if *pid == 0 {
+            close(listener);
+            exit(run_client(id));
+        }
+    }
+    serve(listener, CLIENTS);
+    for &pid in pids.iter() {
+        let mut exit_code = 0;
+        assert_eq!(waitpid(pid as usize, &mut exit_code), pid);
+        assert_eq!(exit_code, 0);
+    }
+    close(listener);
+    assert_eq!(unlink(SERVER_PATH), 0);
+
+    // 没有监听者时连接失败
+    let fd = stream_socket();
+    assert!(connect(fd, &addr) < 0);
+    close(fd);
+}
+
+/// 服务器接受连接后立即关闭
+fn peer_close() {
+    unlink(SERVER_PATH);
+    let addr = SockAddrUn::new(SERVER_PATH).unwrap();
+    let listener = stream_socket();
+    assert_eq!(bind(listener, &addr), 0);
+    assert_eq!(listen(listener, 1), 0);
+    let client = stream_socket();
+    assert_eq!(connect(client, &addr), 0);
+    let mut peer = SockAddrUn::unnamed();
+    let conn = accept_from(listener, &mut peer);
+    assert!(conn > 0);
+    // 客户端没有 bind，对端地址为空
+    assert_eq!(peer.path(), "");
+    close(conn as usize);
+
+    let mut buf = [0u8; 16];
+    assert_eq!(recv(client, &mut buf, MsgFlags::empty()), 0);
+    assert_eq!(send(client, b"late", MsgFlags::NOSIGNAL), -EPIPE);
+    close(client);
+    close(listener);
+    assert_eq!(unlink(SERVER_PATH), 0);
+}
+
+fn datagrams() {
+    let (path_a, path_b) = ("unix_dgram_a\0", "unix_dgram_b\0");
+    unlink(path_a);
+    unlink(path_b);
+    let (addr_a, addr_b) = (
+        SockAddrUn::new(path_a).unwrap(),
+        SockAddrUn::new(path_b).unwrap(),
+    );
+    let a = socket(AF_UNIX, SOCK_DGRAM);
+    let b = socket(AF_UNIX, SOCK_DGRAM);
+    assert!(a > 0 && b > 0);
+    let (a, b) = (a as usize, b as usize);
+    assert_eq!(bind(a, &addr_a), 0);
+    assert_eq!(bind(b, &addr_b), 0);
+
+    assert_eq!(sendto(a, b"first", MsgFlags::empty(), &addr_b), 5);
+    assert_eq!(sendto(a, b"second!", MsgFlags::empty(), &addr_b), 7);
+    let mut buf = [0u8; 32];
+    let mut from = SockAddrUn::unnamed();
+    // 消息边界保留：两次发送对应两次接收
+    assert_eq!(recvfrom(b, &mut buf, MsgFlags::PEEK, &mut from), 5);
+    assert_eq!(recvfrom(b, &mut buf, MsgFlags::empty(), &mut from), 5);
+    assert_eq!(&buf[..5], b"first");
+    assert_eq!(from.path(), "unix_dgram_a");
+    // 缓冲区不足时多余部分被丢弃
+    assert_eq!(recvfrom(b, &mut buf[..3], MsgFlags::empty(), &mut from), 3);
+    assert_eq!(&buf[..3], b"sec");
+    assert!(recv(b, &mut buf, MsgFlags::DONTWAIT) < 0);
+
+    // connect 之后可以直接 send
+    assert_eq!(connect(b, &addr_a), 0);
+    assert_eq!(send(b, b"reply", MsgFlags::empty()), 5);
+    assert_eq!(recvfrom(a, &mut buf, MsgFlags::empty(), &mut from), 5);
+    assert_eq!(from.path(), "unix_dgram_b");
+
+    close(a);
+    close(b);
+    assert_eq!(unlink(path_a), 0);
+    assert_eq!(unlink(path_b), 0);
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    echo_server();
+    peer_close();
+    datagrams();
+    println!("Test unix_socket OK!");
+    0
+}
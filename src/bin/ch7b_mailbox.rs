@@ -10,7 +10,24 @@ const TESTS: &[&str] = &[
     "ch7b_mailbox_limits\0",
 ];
 
-use user_lib::{spawn, waitpid};
+const VERBOSE: &str = "--log=debug\0";
+
+use user_lib::{exec, fork, spawn, waitpid};
+
+/// 失败的测例带上 --log=debug 重新运行一次，输出详细过程便于定位
+fn rerun_verbose(test: &str) {
+    println!("Mailbox tests: rerunning {} with --log=debug", test);
+    let pid = fork();
+    if pid == 0 {
+        exec(
+            test,
+            &[test.as_ptr(), VERBOSE.as_ptr(), core::ptr::null::<u8>()],
+        );
+        panic!("unreachable!");
+    }
+    let mut status: i32 = 0;
+    assert_eq!(waitpid(pid as usize, &mut status), pid);
+}
 
 #[no_mangle]
 pub fn main() -> i32 {
@@ -24,6 +41,9 @@ pub fn main() -> i32 {
             "\x1b[32mMailbox tests: {} exited with code {}\x1b[0m",
             test, status
         );
+        if status != 0 {
+            rerun_verbose(test);
+        }
     }
     println!("Mailbox aggregate passed!");
     0
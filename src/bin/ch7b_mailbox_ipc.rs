@@ -5,6 +5,8 @@
 extern crate user_lib;
 
 use core::mem::size_of;
+use log::debug;
+use user_lib::logging;
 use user_lib::{fork, getpid, mail_read, mail_write, waitpid, yield_};
 
 const MAX_MSG_LEN: usize = 256;
@@ -27,17 +29,17 @@ fn recv_blocking(buf: &mut [u8]) -> usize {
 
 fn run_parent(child_pid: usize) {
     let parent_pid = getpid() as usize;
-    println!("[父进程] 进程号 = {}，子进程 = {}", parent_pid, child_pid);
+    debug!("[父进程] 进程号 = {}，子进程 = {}", parent_pid, child_pid);
 
     let mut handshake = [0u8; 1 + size_of::<usize>()];
     handshake[0] = STAGE_HANDSHAKE;
     handshake[1..].copy_from_slice(&parent_pid.to_le_bytes());
     assert_eq!(mail_write(child_pid, &handshake), handshake.len() as isize);
-    println!("[父进程] 已发送握手 ({} 字节)", handshake.len());
+    debug!("[父进程] 已发送握手 ({} 字节)", handshake.len());
 
     let mut ack = [0u8; 3];
     let got = recv_blocking(&mut ack);
-    println!(
+    debug!(
         "[父进程] 收到握手确认: 长度={}, 字节={:02x} {:02x} {:02x}",
         got, ack[0], ack[1], ack[2]
     );
@@ -48,7 +50,7 @@ fn run_parent(child_pid: usize) {
     let mut large_payload = [LARGE_FILL; LARGE_SEND_LEN];
     large_payload[0] = STAGE_LARGE;
     let wrote = mail_write(child_pid, &large_payload);
-    println!(
+    debug!(
         "[父进程] 发送大负载: 期望={}，实际={}",
         LARGE_SEND_LEN, wrote
     );
@@ -56,7 +58,7 @@ fn run_parent(child_pid: usize) {
 
     let mut large_ack = [0u8; 3];
     let got = recv_blocking(&mut large_ack);
-    println!(
+    debug!(
         "[父进程] 收到大负载确认: 长度={}, 字节={:02x} {:02x} {:02x}",
         got, large_ack[0], large_ack[1], large_ack[2]
     );
@@ -67,7 +69,7 @@ fn run_parent(child_pid: usize) {
 
     let trunc_message = [STAGE_TRUNC, 0xa0, 0xa1, 0xa2, 0xa3, 0xa4];
     let wrote = mail_write(child_pid, &trunc_message);
-    println!(
+    debug!(
         "[父进程] 发送截断负载: 期望={}，实际={}",
         TRUNC_TOTAL_LEN, wrote
     );
@@ -75,7 +77,7 @@ fn run_parent(child_pid: usize) {
 
     let mut trunc_ack = [0u8; 4];
     let got = recv_blocking(&mut trunc_ack);
-    println!(
+    debug!(
         "[父进程] 收到截断确认: 长度={}, 字节={:02x} {:02x} {:02x} {:02x}",
         got, trunc_ack[0], trunc_ack[1], trunc_ack[2], trunc_ack[3]
     );
@@ -89,7 +91,7 @@ fn run_parent(child_pid: usize) {
 fn run_child() -> i32 {
     let mut buf = [0u8; MAX_MSG_LEN];
     let handshake_len = recv_blocking(&mut buf);
-    println!(
+    debug!(
         "[子进程] 收到握手 长度={}，首字节={:02x}",
         handshake_len, buf[0]
     );
@@ -98,14 +100,14 @@ fn run_child() -> i32 {
     let mut parent_bytes = [0u8; size_of::<usize>()];
     parent_bytes.copy_from_slice(&buf[1..handshake_len]);
     let parent_pid = usize::from_le_bytes(parent_bytes);
-    println!("[子进程] 父进程号 = {}", parent_pid);
+    debug!("[子进程] 父进程号 = {}", parent_pid);
 
     let ack = [STAGE_HANDSHAKE, b'O', b'K'];
     assert_eq!(mail_write(parent_pid, &ack), ack.len() as isize);
-    println!("[子进程] 已发送握手确认");
+    debug!("[子进程] 已发送握手确认");
 
     let large_len = recv_blocking(&mut buf);
-    println!("[子进程] 收到大负载 长度={}", large_len);
+    debug!("[子进程] 收到大负载 长度={}", large_len);
     assert_eq!(large_len, MAX_MSG_LEN);
     assert_eq!(buf[0], STAGE_LARGE);
     for &byte in buf[1..large_len].iter() {
@@ -114,21 +116,22 @@ fn run_child() -> i32 {
     let len_bytes = (large_len as u16).to_le_bytes();
     let large_ack = [STAGE_LARGE, len_bytes[0], len_bytes[1]];
     assert_eq!(mail_write(parent_pid, &large_ack), large_ack.len() as isize);
-    println!("[子进程] 已发送大负载确认 长度={}", large_ack.len());
+    debug!("[子进程] 已发送大负载确认 长度={}", large_ack.len());
 
     let mut small_buf = [0u8; 4];
     let trunc_len = recv_blocking(&mut small_buf);
-    println!("[子进程] 收到截断负载 长度={}", trunc_len);
+    debug!("[子进程] 收到截断负载 长度={}", trunc_len);
     assert_eq!(trunc_len, TRUNC_TOTAL_LEN);
     assert_eq!(small_buf[0], STAGE_TRUNC);
     assert_eq!(mail_write(parent_pid, &small_buf), small_buf.len() as isize);
-    println!("[子进程] 已发送截断确认 长度={}", small_buf.len());
+    debug!("[子进程] 已发送截断确认 长度={}", small_buf.len());
 
     0
 }
 
 #[no_mangle]
-pub fn main() -> i32 {
+pub fn main(_argc: usize, argv: &[&str]) -> i32 {
+    logging::parse_args(argv);
     let pid = fork();
     if pid == 0 {
         return run_child();
@@ -6,11 +6,18 @@ extern crate alloc;
 extern crate user_lib;
 
 use alloc::format;
-use user_lib::{close, fork, getpid, pipe, read, wait, write};
+use user_lib::rand::Rng;
+use user_lib::{close, fork, pipe, read, wait, write};
 
 const LENGTH: usize = 3000;
+const DEFAULT_SEED: u64 = 0x5eed;
 #[no_mangle]
-pub fn main() -> i32 {
+pub fn main(argc: usize, argv: &[&str]) -> i32 {
+    // rerun with --seed=N to reproduce a failure, or --seed=random
+    let mut rng = Rng::from_args(argv, DEFAULT_SEED);
+    if argc > 1 {
+        println!("seed = {}", rng.seed());
+    }
     // create pipes
     // parent write to child
     let mut down_pipe_fd = [0usize; 2];
@@ -39,9 +46,7 @@ pub fn main() -> i32 {
         // close write end of up pipe
         close(up_pipe_fd[1]);
         // generate a long random string
-        for ch in random_str.iter_mut().take(LENGTH) {
-            *ch = getpid() as u8;
-        }
+        rng.fill_bytes(&mut random_str);
         // send it
         assert_eq!(
             write(down_pipe_fd[1], &random_str) as usize,
@@ -60,7 +65,9 @@ pub fn main() -> i32 {
         assert_eq!(
             sum,
             str::parse::<usize>(core::str::from_utf8(&child_result[..result_len]).unwrap())
-                .unwrap()
+                .unwrap(),
+            "seed = {}",
+            rng.seed()
         );
         let mut _unused: i32 = 0;
         wait(&mut _unused);
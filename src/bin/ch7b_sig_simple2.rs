@@ -3,6 +3,7 @@
 
 extern crate user_lib;
 
+use user_lib::time::Duration;
 use user_lib::*;
 
 fn func() {
@@ -23,13 +24,13 @@ pub fn main() -> i32 {
             if sigaction(SIGUSR1, Some(&new), Some(&mut old)) < 0 {
                 panic!("Sigaction failed!");
             }
-            sleep(1000);
+            sleep_yield(Duration::from_millis(1000));
             println!("signal_simple2: child done");
             exit(0);
         }
         core::cmp::Ordering::Greater => {
             println!("signal_simple2: parent kill child");
-            sleep(500);
+            sleep_yield(Duration::from_millis(500));
             if kill(pid as usize, SIGUSR1) < 0 {
                 println!("Kill failed!");
                 exit(1);
@@ -3,6 +3,7 @@
 
 extern crate user_lib;
 
+use user_lib::time::Duration;
 use user_lib::*;
 
 fn func() {
@@ -84,10 +85,10 @@ fn kernel_sig_test_stop_cont() {
     let pid = fork();
     if pid == 0 {
         kill(getpid() as usize, SIGSTOP);
-        sleep(500);
+        sleep_yield(Duration::from_millis(500));
         exit(-1);
     } else {
-        sleep(1000);
+        sleep_yield(Duration::from_millis(1000));
         kill(pid as usize, SIGCONT);
         let mut exit_code = 0;
         wait(&mut exit_code);
@@ -129,7 +130,7 @@ fn final_sig_test() {
         write(pipe_fd[1], &[0u8]);
         close(pipe_fd[1]);
         loop {
-            sleep(1);
+            sleep_yield(Duration::from_millis(1));
         }
     } else {
         close(pipe_fd[1]);
@@ -140,7 +141,7 @@ fn final_sig_test() {
             println!("Kill failed!");
             exit(-1);
         }
-        sleep(100);
+        sleep_yield(Duration::from_millis(100));
         kill(pid as usize, SIGKILL);
     }
 }
@@ -0,0 +1,78 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use core::hint::black_box;
+use user_lib::backtrace::{backtrace, print_backtrace, resolve, MAX_FRAMES};
+use user_lib::{exit, fork, thread_create, waitpid, waittid};
+
+/*
+理想结果：能够得到 inner -> middle -> outer 的调用链；
+线程中的 panic 会打印出 Backtrace，线程以 -1 退出。
+*/
+
+// 调用之后还有操作，避免尾调用优化吞掉栈帧
+#[inline(never)]
+fn inner(frames: &mut [usize]) -> usize {
+    let n = backtrace(frames);
+    black_box(n)
+}
+
+#[inline(never)]
+fn middle(frames: &mut [usize]) -> usize {
+    let n = inner(frames);
+    black_box(n)
+}
+
+#[inline(never)]
+fn outer(frames: &mut [usize]) -> usize {
+    let n = middle(frames);
+    black_box(n)
+}
+
+/// 返回地址应落在调用者函数体内
+fn check_frame(ra: usize, func: usize, name: &str) {
+    assert!(ra > func, "{:#x} is not inside {}", ra, name);
+    if let Some((sym, off)) = resolve(ra) {
+        println!("{:#x} {}+{:#x}", ra, sym, off);
+        assert!(sym.ends_with(name), "{} != {}", sym, name);
+    }
+}
+
+#[inline(never)]
+fn thread_helper(depth: usize) -> usize {
+    if depth == 0 {
+        panic!("panic inside thread helper");
+    }
+    black_box(thread_helper(depth - 1)) + 1
+}
+
+fn thread_entry() -> ! {
+    thread_helper(3);
+    exit(0)
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let mut frames = [0usize; MAX_FRAMES];
+    let n = outer(&mut frames);
+    assert!(n >= 3, "only {} frames", n);
+    check_frame(frames[0], inner as usize, "inner");
+    check_frame(frames[1], middle as usize, "middle");
+    check_frame(frames[2], outer as usize, "outer");
+    print_backtrace();
+
+    let pid = fork();
+    if pid == 0 {
+        let tid = thread_create(thread_entry as usize, 0);
+        // panic 只结束该线程时，由主线程把线程的退出码 -1 传出来
+        exit(waittid(tid as usize) as i32);
+    }
+    let mut exit_code = 0;
+    assert_eq!(waitpid(pid as usize, &mut exit_code), pid);
+    assert_eq!(exit_code, -1);
+    println!("Test backtrace OK!");
+    0
+}
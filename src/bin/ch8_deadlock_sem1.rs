@@ -6,8 +6,9 @@ extern crate alloc;
 #[macro_use]
 extern crate user_lib;
 
+use user_lib::time::Duration;
 use user_lib::{
-    enable_deadlock_detect, exit, semaphore_create, semaphore_down, semaphore_up, sleep,
+    enable_deadlock_detect, exit, semaphore_create, semaphore_down, semaphore_up, sleep_yield,
 };
 use user_lib::{gettid, thread_create, waittid};
 
@@ -129,7 +130,7 @@ pub fn main() -> i32 {
     }
 
     // 暂停一段时间以确保子线程阻塞在屏障上
-    sleep(500);
+    sleep_yield(Duration::from_millis(500));
     // 释放屏障令牌触发资源竞争
     for _ in 0..THREAD_N {
         semaphore_up(SEM_BARRIER);
@@ -6,8 +6,9 @@ extern crate alloc;
 #[macro_use]
 extern crate user_lib;
 
+use user_lib::time::Duration;
 use user_lib::{
-    enable_deadlock_detect, exit, semaphore_create, semaphore_down, semaphore_up, sleep,
+    enable_deadlock_detect, exit, semaphore_create, semaphore_down, semaphore_up, sleep_yield,
 };
 use user_lib::{gettid, thread_create, waittid};
 
@@ -85,7 +86,7 @@ pub fn main() -> i32 {
     }
 
     // 等待子线程全部进入就绪状态
-    sleep(1000);
+    sleep_yield(Duration::from_millis(1000));
     // 释放屏障令牌以让子线程继续执行
     for _ in 0..THREAD_N {
         semaphore_up(0);
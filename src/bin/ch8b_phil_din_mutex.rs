@@ -7,7 +7,8 @@ extern crate alloc;
 extern crate user_lib;
 
 use alloc::vec::Vec;
-use user_lib::{exit, get_time, sleep_blocking};
+use user_lib::time::Duration;
+use user_lib::{exit, get_time, sleep_yield};
 use user_lib::{mutex_blocking_create, mutex_lock, mutex_unlock};
 use user_lib::{thread_create, waittid};
 
@@ -42,7 +43,7 @@ fn philosopher_dining_problem(id: *const usize) {
         unsafe {
             THINK[id][2 * round] = get_time_u();
         }
-        sleep_blocking(ARR[id][2 * round]);
+        sleep_yield(Duration::from_millis(ARR[id][2 * round] as u64));
         unsafe {
             THINK[id][2 * round + 1] = get_time_u();
         }
@@ -53,7 +54,7 @@ fn philosopher_dining_problem(id: *const usize) {
         unsafe {
             EAT[id][2 * round] = get_time_u();
         }
-        sleep_blocking(ARR[id][2 * round + 1]);
+        sleep_yield(Duration::from_millis(ARR[id][2 * round + 1] as u64));
         unsafe {
             EAT[id][2 * round + 1] = get_time_u();
         }
@@ -6,13 +6,14 @@ extern crate alloc;
 extern crate user_lib;
 
 use user_lib::exit;
+use user_lib::time::Duration;
 use user_lib::{semaphore_create, semaphore_down, semaphore_up};
-use user_lib::{sleep_blocking, thread_create, waittid};
+use user_lib::{sleep_yield, thread_create, waittid};
 
 const SEM_SYNC: usize = 0;
 
 unsafe fn first() -> ! {
-    sleep_blocking(10);
+    sleep_yield(Duration::from_millis(10));
     println!("First work and wakeup Second");
     semaphore_up(SEM_SYNC);
     exit(0)
@@ -6,10 +6,11 @@ extern crate alloc;
 extern crate user_lib;
 
 use user_lib::exit;
+use user_lib::time::Duration;
 use user_lib::{
     condvar_create, condvar_signal, condvar_wait, mutex_blocking_create, mutex_lock, mutex_unlock,
 };
-use user_lib::{sleep_blocking, thread_create, waittid};
+use user_lib::{sleep_yield, thread_create, waittid};
 
 static mut A: usize = 0;
 
@@ -17,7 +18,7 @@ const CONDVAR_ID: usize = 0;
 const MUTEX_ID: usize = 0;
 
 unsafe fn first() -> ! {
-    sleep_blocking(10);
+    sleep_yield(Duration::from_millis(10));
     println!("First work, Change A --> 1 and wakeup Second");
     mutex_lock(MUTEX_ID);
     A = 1;
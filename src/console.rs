@@ -1,30 +1,50 @@
 use alloc::collections::vec_deque::VecDeque;
 use alloc::sync::Arc;
 use core::fmt::{self, Write};
+use core::sync::atomic::{AtomicBool, Ordering};
 use spin::mutex::Mutex;
 
 pub const STDIN: usize = 0;
 pub const STDOUT: usize = 1;
+pub const STDERR: usize = 2;
 
 const CONSOLE_BUFFER_SIZE: usize = 256 * 10;
 
 use super::{read, write};
 use lazy_static::*;
 
-struct ConsoleBuffer(VecDeque<u8>);
+/// How `print!`/`println!` output is buffered before reaching `STDOUT`.
+/// `eprint!`/`eprintln!` always bypass the buffer.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum BufferMode {
+    /// write every formatted piece immediately
+    Unbuffered,
+    /// flush on newline or when the buffer is full (default)
+    Line,
+    /// flush only when the buffer is full or on `flush()`
+    Full,
+}
+
+struct ConsoleBuffer {
+    buf: VecDeque<u8>,
+    mode: BufferMode,
+}
 
 lazy_static! {
     static ref CONSOLE_BUFFER: Arc<Mutex<ConsoleBuffer>> = {
-        let buffer = VecDeque::<u8>::with_capacity(CONSOLE_BUFFER_SIZE);
-        Arc::new(Mutex::new(ConsoleBuffer(buffer)))
+        let buf = VecDeque::<u8>::with_capacity(CONSOLE_BUFFER_SIZE);
+        Arc::new(Mutex::new(ConsoleBuffer {
+            buf,
+            mode: BufferMode::Line,
+        }))
     };
 }
 
 impl ConsoleBuffer {
     fn flush(&mut self) -> isize {
-        let s: &[u8] = self.0.make_contiguous();
+        let s: &[u8] = self.buf.make_contiguous();
         let ret = write(STDOUT, s);
-        self.0.clear();
+        self.buf.clear();
         ret
     }
 }
@@ -32,11 +52,27 @@ impl ConsoleBuffer {
 impl Write for ConsoleBuffer {
     fn write_str(&mut self, s: &str) -> fmt::Result {
         for c in s.as_bytes().iter() {
-            self.0.push_back(*c);
-            if (*c == b'\n' || self.0.len() == CONSOLE_BUFFER_SIZE) && -1 == self.flush() {
+            self.buf.push_back(*c);
+            let line_end = *c == b'\n' && self.mode == BufferMode::Line;
+            if (line_end || self.buf.len() == CONSOLE_BUFFER_SIZE) && self.flush() < 0 {
                 return Err(fmt::Error);
             }
         }
+        if self.mode == BufferMode::Unbuffered && !self.buf.is_empty() && self.flush() < 0 {
+            return Err(fmt::Error);
+        }
+        Ok(())
+    }
+}
+
+/// Writes straight to an fd, bypassing the buffer.
+struct Unbuffered(usize);
+
+impl Write for Unbuffered {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if write(self.0, s.as_bytes()) < 0 {
+            return Err(fmt::Error);
+        }
         Ok(())
     }
 }
@@ -49,6 +85,36 @@ pub fn print(args: fmt::Arguments) {
     buf.write_fmt(args);
 }
 
+#[allow(unused)]
+pub fn eprint(args: fmt::Arguments) {
+    // 与 print 相同，stderr 被关闭时也不能触发 panic
+    Unbuffered(STDERR).write_fmt(args);
+}
+
+static PANIC_TO_STDERR: AtomicBool = AtomicBool::new(false);
+
+/// Send panic messages and backtraces to `STDERR` instead of `STDOUT`. Off by
+/// default because the ch2~ch5 kernels have no fd table and reject writes to
+/// `STDERR`.
+pub fn set_panic_to_stderr(enabled: bool) {
+    PANIC_TO_STDERR.store(enabled, Ordering::Relaxed);
+}
+
+/// Print panic messages and backtraces behind whatever stdout has buffered.
+#[allow(unused)]
+pub(crate) fn panic_print(args: fmt::Arguments) {
+    // panic 可能发生在 print 持有缓冲区锁时，此时不能再等待该锁
+    if let Some(mut buf) = CONSOLE_BUFFER.try_lock() {
+        buf.flush();
+    }
+    let fd = if PANIC_TO_STDERR.load(Ordering::Relaxed) {
+        STDERR
+    } else {
+        STDOUT
+    };
+    Unbuffered(fd).write_fmt(args);
+}
+
 #[macro_export]
 macro_rules! print {
     ($fmt: literal $(, $($arg: tt)+)?) => {
@@ -63,6 +129,26 @@ macro_rules! println {
     }
 }
 
+macro_rules! panic_println {
+    ($fmt: literal $(, $($arg: tt)+)?) => {
+        $crate::console::panic_print(format_args!(concat!($fmt, "\n") $(, $($arg)+)?));
+    }
+}
+
+#[macro_export]
+macro_rules! eprint {
+    ($fmt: literal $(, $($arg: tt)+)?) => {
+        $crate::console::eprint(format_args!($fmt $(, $($arg)+)?));
+    }
+}
+
+#[macro_export]
+macro_rules! eprintln {
+    ($fmt: literal $(, $($arg: tt)+)?) => {
+        $crate::console::eprint(format_args!(concat!($fmt, "\n") $(, $($arg)+)?));
+    }
+}
+
 pub fn getchar() -> u8 {
     let mut c = [0u8; 1];
     read(STDIN, &mut c);
@@ -73,3 +159,14 @@ pub fn flush() {
     let mut buf = CONSOLE_BUFFER.lock();
     buf.flush();
 }
+
+/// Switch stdout buffering, flushing whatever is already buffered first.
+pub fn set_buffer_mode(mode: BufferMode) {
+    let mut buf = CONSOLE_BUFFER.lock();
+    buf.flush();
+    buf.mode = mode;
+}
+
+pub fn buffer_mode() -> BufferMode {
+    CONSOLE_BUFFER.lock().mode
+}
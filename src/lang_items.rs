@@ -1,17 +1,19 @@
+use crate::backtrace::print_backtrace;
 use crate::exit;
 
 #[panic_handler]
 fn panic_handler(panic_info: &core::panic::PanicInfo) -> ! {
     let err = panic_info.message().unwrap();
     if let Some(location) = panic_info.location() {
-        println!(
+        panic_println!(
             "Panicked at {}:{}, {}",
             location.file(),
             location.line(),
             err
         );
     } else {
-        println!("Panicked: {}", err);
+        panic_println!("Panicked: {}", err);
     }
+    print_backtrace();
     exit(-1);
 }
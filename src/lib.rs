@@ -9,12 +9,21 @@ extern crate bitflags;
 extern crate core;
 #[macro_use]
 pub mod console;
+pub mod backtrace;
 mod lang_items;
+pub mod logging;
+pub mod mailbox;
+pub mod net;
+pub mod rand;
+pub mod readline;
 mod syscall;
+pub mod time;
 
 use alloc::vec::Vec;
 use buddy_system_allocator::LockedHeap;
-pub use console::{flush, STDIN, STDOUT};
+pub use console::{
+    buffer_mode, flush, set_buffer_mode, set_panic_to_stderr, BufferMode, STDERR, STDIN, STDOUT,
+};
 pub use syscall::*;
 
 const USER_HEAP_SIZE: usize = 16384;
@@ -65,6 +74,7 @@ pub extern "C" fn _start(argc: usize, argv: usize) -> ! {
             .unwrap(),
         );
     }
+    logging::init();
     exit(main(argc, v.as_slice()));
 }
 
@@ -78,8 +88,21 @@ bitflags! {
     pub struct OpenFlags: u32 {
         const WRONLY = 1 << 0;
         const RDWR = 1 << 1;
+        /// with `CREATE`, fail if the file already exists
+        const EXCL = 1 << 7;
+        /// every write goes to the current end of the file
+        // not Linux's O_APPEND (0o2000), which is this ABI's TRUNC bit
+        const APPEND = 1 << 8;
         const CREATE = 1 << 9;
         const TRUNC = 1 << 10;
+        /// reads and writes that would block fail with `EAGAIN` instead
+        const NONBLOCK = 1 << 11;
+        /// fail unless the path names a directory
+        const DIRECTORY = 1 << 16;
+        /// fail if the last path component is a symbolic link
+        const NOFOLLOW = 1 << 17;
+        /// close the fd when `exec` succeeds
+        const CLOEXEC = 1 << 19;
     }
 }
 
@@ -100,6 +123,34 @@ impl TimeVal {
     }
 }
 
+#[repr(C)]
+#[derive(Debug, Default)]
+pub struct TimeSpec {
+    pub sec: usize,
+    pub nsec: usize,
+}
+
+impl TimeSpec {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl From<time::Duration> for TimeSpec {
+    fn from(d: time::Duration) -> Self {
+        Self {
+            sec: d.as_secs() as usize,
+            nsec: d.subsec_nanos() as usize,
+        }
+    }
+}
+
+impl From<TimeSpec> for time::Duration {
+    fn from(ts: TimeSpec) -> Self {
+        time::Duration::new(ts.sec as u64, ts.nsec as u32)
+    }
+}
+
 #[derive(Copy, Clone, PartialEq, Debug)]
 pub enum TaskStatus {
     UnInit,
@@ -108,6 +159,24 @@ pub enum TaskStatus {
     Exited,
 }
 
+/// Physical memory usage as seen by the kernel, in 4 KiB frames/pages.
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MemInfo {
+    /// frames managed by the frame allocator
+    pub total_frames: usize,
+    /// frames not currently allocated
+    pub free_frames: usize,
+    /// user pages mapped in the caller's address space
+    pub resident_pages: usize,
+}
+
+impl MemInfo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
 #[repr(C)]
 pub enum TraceRequest {
     Read,
@@ -126,8 +195,21 @@ pub struct Stat {
     pub mode: StatMode,
     /// number of hard links
     pub nlink: u32,
+    /// total size in bytes
+    pub size: u64,
+    /// preferred block size for I/O
+    pub blksize: u32,
+    pad0: u32,
+    /// number of 512-byte blocks allocated
+    pub blocks: u64,
+    /// last access, in nanoseconds since the Unix epoch
+    pub atime: u64,
+    /// last modification of the contents
+    pub mtime: u64,
+    /// last change of the contents or the inode
+    pub ctime: u64,
     /// unused pad
-    pad: [u64; 7],
+    pad: u64,
 }
 
 impl Stat {
@@ -137,11 +219,33 @@ impl Stat {
             ino: 0,
             mode: StatMode::NULL,
             nlink: 0,
-            pad: [0; 7],
+            size: 0,
+            blksize: 0,
+            pad0: 0,
+            blocks: 0,
+            atime: 0,
+            mtime: 0,
+            ctime: 0,
+            pad: 0,
         }
     }
+
+    pub fn accessed(&self) -> time::SystemTime {
+        time::UNIX_EPOCH + time::Duration::from_nanos(self.atime)
+    }
+
+    pub fn modified(&self) -> time::SystemTime {
+        time::UNIX_EPOCH + time::Duration::from_nanos(self.mtime)
+    }
+
+    pub fn changed(&self) -> time::SystemTime {
+        time::UNIX_EPOCH + time::Duration::from_nanos(self.ctime)
+    }
 }
 
+// the extended fields live in what used to be padding, the ABI size is unchanged
+const _: () = assert!(core::mem::size_of::<Stat>() == 80);
+
 impl Default for Stat {
     fn default() -> Self {
         Self::new()
@@ -149,22 +253,54 @@ impl Default for Stat {
 }
 
 bitflags! {
+    /// The file type is a 4-bit field, not a set of independent flags: `LNK`
+    /// includes the bits of `FILE`, so `contains` must not be used to check
+    /// the type. Compare `file_type()` with `==` instead.
     pub struct StatMode: u32 {
         /// directory
         const DIR   = 0o040000;
         /// ordinary regular file
         const FILE  = 0o100000;
+        /// symbolic link
+        const LNK   = 0o120000;
+        /// named pipe
+        const FIFO  = 0o010000;
     }
 }
 
 impl StatMode {
     pub const NULL: StatMode = StatMode::empty();
+    const TYPE_MASK: u32 = 0o170000;
+
+    /// Only the file type bits, one of `DIR`, `FILE`, `LNK` or `FIFO`.
+    pub fn file_type(self) -> StatMode {
+        StatMode::from_bits_truncate(self.bits & Self::TYPE_MASK)
+    }
 }
 
+/// Returned (negated) by `sleep` when a signal interrupts it.
+pub const EINTR: isize = 4;
+/// Returned (negated) by operations on `OpenFlags::NONBLOCK` fds that would block.
+pub const EAGAIN: isize = 11;
+/// Returned (negated) by writes to a pipe without readers, unless `SIGPIPE`
+/// killed the writer first.
+pub const EPIPE: isize = 32;
+
 const AT_FDCWD: isize = -100;
+const AT_SYMLINK_NOFOLLOW: usize = 0x100;
+const AT_REMOVEDIR: usize = 0x200;
+
+/// Permission bits given to files created by `open`.
+pub const DEFAULT_FILE_MODE: u32 = 0o644;
 
 pub fn open(path: &str, flags: OpenFlags) -> isize {
-    sys_openat(AT_FDCWD as usize, path, flags.bits, OpenFlags::RDWR.bits)
+    open_with_mode(path, flags, DEFAULT_FILE_MODE)
+}
+
+/// Like `open`, with the permission bits for a file created by
+/// `OpenFlags::CREATE`. `mode` is ignored if the file already exists.
+pub fn open_with_mode(path: &str, flags: OpenFlags, mode: u32) -> isize {
+    sys_openat(AT_FDCWD as usize, path, flags.bits, mode)
 }
 
 pub fn close(fd: usize) -> isize {
@@ -190,18 +326,118 @@ pub fn unlink(path: &str) -> isize {
     sys_unlinkat(AT_FDCWD as usize, path, 0)
 }
 
+/// Create a symbolic link at `link_path` pointing to `target`. The target
+/// need not exist; a relative target is resolved from the link's directory.
+pub fn symlink(target: &str, link_path: &str) -> isize {
+    sys_symlinkat(target, AT_FDCWD as usize, link_path)
+}
+
+/// Copy the target of the symbolic link `path` into `buf`, without a trailing
+/// NUL. Returns the number of bytes written; longer targets are cut off.
+pub fn readlink(path: &str, buf: &mut [u8]) -> isize {
+    sys_readlinkat(AT_FDCWD as usize, path, buf)
+}
+
+pub fn mkdir(path: &str) -> isize {
+    sys_mkdirat(AT_FDCWD as usize, path, 0o755)
+}
+
+/// Create a named pipe at `path`. Opening it for reading blocks until a
+/// writer opens it too, and vice versa, unless `OpenFlags::NONBLOCK` is given.
+pub fn mkfifo(path: &str) -> isize {
+    sys_mknodat(AT_FDCWD as usize, path, StatMode::FIFO.bits | 0o644, 0)
+}
+
+/// Remove the empty directory `path`.
+pub fn rmdir(path: &str) -> isize {
+    sys_unlinkat(AT_FDCWD as usize, path, AT_REMOVEDIR)
+}
+
+/// Move `old_path` to `new_path`, atomically replacing any file already there.
+/// The inode, and so its number and hard links, is preserved.
+pub fn rename(old_path: &str, new_path: &str) -> isize {
+    sys_renameat(AT_FDCWD as usize, old_path, AT_FDCWD as usize, new_path)
+}
+
 pub fn fstat(fd: usize, st: &mut Stat) -> isize {
     sys_fstat(fd, st)
 }
 
+/// Like `fstat`, but looks the file up by path instead of an open fd.
+pub fn stat(path: &str, st: &mut Stat) -> isize {
+    sys_fstatat(AT_FDCWD as usize, path, st, 0)
+}
+
+/// Like `stat`, but describes a symbolic link itself rather than its target.
+pub fn lstat(path: &str, st: &mut Stat) -> isize {
+    sys_fstatat(AT_FDCWD as usize, path, st, AT_SYMLINK_NOFOLLOW)
+}
+
+/// Set the size of `path` to `len` bytes. Growing a file fills it with zeros.
+pub fn truncate(path: &str, len: usize) -> isize {
+    sys_truncate(path, len)
+}
+
+pub fn ftruncate(fd: usize, len: usize) -> isize {
+    sys_ftruncate(fd, len)
+}
+
 pub fn mail_read(buf: &mut [u8]) -> isize {
-    sys_mail_read(buf)
+    sys_mail_read(buf, MailFlags::empty().bits, core::ptr::null_mut())
+}
+
+bitflags! {
+    pub struct MailFlags: u32 {
+        /// wait for a message instead of failing on an empty mailbox
+        const BLOCK = 1;
+    }
+}
+
+/// Like `mail_read`, but waits until a message arrives.
+pub fn mail_read_blocking(buf: &mut [u8]) -> isize {
+    sys_mail_read(buf, MailFlags::BLOCK.bits, core::ptr::null_mut())
+}
+
+/// Like `mail_read`, with `flags`. Returns the result of the read together
+/// with the pid of the message's sender, which is 0 if the read failed.
+pub fn mail_read_with_flags(buf: &mut [u8], flags: MailFlags) -> (isize, usize) {
+    let mut sender = 0;
+    let len = sys_mail_read(buf, flags.bits, &mut sender);
+    (len, sender)
 }
 
 pub fn mail_write(pid: usize, buf: &[u8]) -> isize {
     sys_mail_write(pid, buf)
 }
 
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MailStatus {
+    /// messages waiting to be read
+    pub pending: usize,
+    /// messages that can still be written before the mailbox is full
+    pub free: usize,
+}
+
+impl MailStatus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// Query the mailbox of process `pid`.
+pub fn mail_status(pid: usize, status: &mut MailStatus) -> isize {
+    sys_mail_status(pid, status)
+}
+
+/// Open an fd for the caller's own mailbox, so `poll` can wait for mail
+/// together with pipes and the console. It reports `PollEvents::IN` while a
+/// message is pending, and `read` on it takes one message like `mail_read`.
+/// Closing it leaves the mailbox untouched.
+pub fn mailbox_fd() -> isize {
+    sys_mailbox_fd()
+}
+
 pub fn exit(exit_code: i32) -> ! {
     flush();
     sys_exit(exit_code);
@@ -211,10 +447,21 @@ pub fn yield_() -> isize {
     sys_yield()
 }
 
+/// Wall-clock time in milliseconds, or -1 on failure. See `time::Instant` for
+/// a monotonic clock with finer resolution.
 pub fn get_time() -> isize {
     let mut time = TimeVal::new();
     match sys_get_time(&mut time, 0) {
-        0 => ((time.sec & 0xffff) * 1000 + time.usec / 1000) as isize,
+        0 => (time.sec * 1000 + time.usec / 1000) as isize,
+        _ => -1,
+    }
+}
+
+/// Same as `get_time`, in microseconds.
+pub fn get_time_us() -> isize {
+    let mut time = TimeVal::new();
+    match sys_get_time(&mut time, 0) {
+        0 => (time.sec * 1_000_000 + time.usec) as isize,
         _ => -1,
     }
 }
@@ -235,22 +482,27 @@ pub fn set_priority(prio: isize) -> isize {
     sys_set_priority(prio)
 }
 
-pub fn wait(exit_code: &mut i32) -> isize {
-    loop {
-        match sys_waitpid(-1, exit_code as *mut _) {
-            -2 => {
-                sys_yield();
-            }
-            n => {
-                return n;
-            }
-        }
+bitflags! {
+    pub struct WaitFlags: u32 {
+        /// return -2 immediately instead of blocking when no child/thread has exited yet
+        const NOHANG = 1 << 0;
     }
 }
 
+/// Block until any child process exits.
+pub fn wait(exit_code: &mut i32) -> isize {
+    waitpid_blocking(-1, exit_code)
+}
+
+/// Block until the child process `pid` exits.
 pub fn waitpid(pid: usize, exit_code: &mut i32) -> isize {
+    waitpid_blocking(pid as isize, exit_code)
+}
+
+// kernels that do not block in waitpid return -2 while the child runs
+fn waitpid_blocking(pid: isize, exit_code: &mut i32) -> isize {
     loop {
-        match sys_waitpid(pid as isize, exit_code as *mut _) {
+        match sys_waitpid(pid, exit_code as *mut _, WaitFlags::empty().bits) {
             -2 => {
                 sys_yield();
             }
@@ -261,24 +513,142 @@ pub fn waitpid(pid: usize, exit_code: &mut i32) -> isize {
     }
 }
 
-pub fn sleep_blocking(sleep_ms: usize) {
-    sys_sleep(sleep_ms);
+/// Like `waitpid`, but `pid == -1` waits for any child and `WaitFlags::NOHANG`
+/// turns it into a poll returning -2 while the child is still running.
+pub fn waitpid_with_flags(pid: isize, exit_code: &mut i32, flags: WaitFlags) -> isize {
+    sys_waitpid(pid, exit_code as *mut _, flags.bits)
 }
 
-pub fn sleep(period_ms: usize) {
-    let start = get_time();
-    while get_time() < start + period_ms as isize {
+/// Sleep in the kernel for `duration`. Returns the unslept remainder, which is
+/// nonzero only if a signal cut the sleep short, or the negative error code
+/// the kernel failed with.
+pub fn sleep(duration: time::Duration) -> Result<time::Duration, isize> {
+    let req = TimeSpec::from(duration);
+    let mut rem = TimeSpec::new();
+    match sys_nanosleep(&req, &mut rem) {
+        0 => Ok(time::Duration::ZERO),
+        ret if ret == -EINTR => Ok(rem.into()),
+        ret => Err(ret),
+    }
+}
+
+/// Sleep by yielding until `get_time` has advanced by `duration`. Works on
+/// kernels without nanosleep, at millisecond granularity.
+pub fn sleep_yield(duration: time::Duration) {
+    let end = get_time() + duration.as_millis() as isize;
+    while get_time() < end {
         sys_yield();
     }
 }
+
+bitflags! {
+    pub struct ProtFlags: u32 {
+        const READ = 1 << 0;
+        const WRITE = 1 << 1;
+        const EXEC = 1 << 2;
+    }
+}
+
+impl ProtFlags {
+    pub const NONE: ProtFlags = ProtFlags::empty();
+}
+
+bitflags! {
+    pub struct MapFlags: u32 {
+        /// writes are visible to other mappings and carried through to the file
+        const SHARED = 0x01;
+        /// writes are copy-on-write and never reach the file
+        const PRIVATE = 0x02;
+        /// map exactly at `start`, replacing whatever was there
+        const FIXED = 0x10;
+        /// not backed by a file, zero-filled; `fd` and `offset` are ignored
+        const ANONYMOUS = 0x20;
+        /// like `FIXED`, but fail instead of replacing an existing mapping
+        const FIXED_NOREPLACE = 0x100000;
+    }
+}
+
+/// Map `[start, start + len)` with raw `prot` bits, returning 0 on success and
+/// -1 on failure. This is the interface of the ch4 lab, see `mmap_with` for
+/// the full one.
 pub fn mmap(start: usize, len: usize, prot: usize) -> isize {
-    sys_mmap(start, len, prot)
+    let flags = MapFlags::PRIVATE | MapFlags::ANONYMOUS | MapFlags::FIXED_NOREPLACE;
+    match sys_mmap(start, len, prot, flags.bits as usize, usize::MAX, 0) {
+        // kernels with full mmap return the mapped address
+        ret if ret == start as isize => 0,
+        // and -errno on failure, which the ch4 tests expect as -1
+        ret if ret < 0 => -1,
+        ret => ret,
+    }
+}
+
+/// Map `len` bytes of `fd` from `offset`, or anonymous memory if `fd` is
+/// `None`. `start` is a hint unless `FIXED`/`FIXED_NOREPLACE` is given.
+/// Returns the mapped address, or a negative error code on failure.
+pub fn mmap_with(
+    start: usize,
+    len: usize,
+    prot: ProtFlags,
+    flags: MapFlags,
+    fd: Option<usize>,
+    offset: usize,
+) -> isize {
+    sys_mmap(
+        start,
+        len,
+        prot.bits as usize,
+        flags.bits as usize,
+        fd.unwrap_or(usize::MAX),
+        offset,
+    )
 }
 
 pub fn munmap(start: usize, len: usize) -> isize {
     sys_munmap(start, len)
 }
 
+/// Change the protection of the mapped pages in `[start, start + len)`.
+/// `start` must be page aligned and the whole range mapped.
+pub fn mprotect(start: usize, len: usize, prot: ProtFlags) -> isize {
+    sys_mprotect(start, len, prot.bits as usize)
+}
+
+bitflags! {
+    pub struct ShmFlags: u32 {
+        /// create the segment if no segment with this key exists
+        const CREATE = 0o1000;
+        /// with `CREATE`, fail if the segment already exists
+        const EXCL = 0o2000;
+        /// attach read-only
+        const RDONLY = 0o10000;
+    }
+}
+
+/// `shm_get` key that always creates a new segment.
+pub const SHM_PRIVATE: usize = 0;
+const SHM_RMID: usize = 0;
+
+/// Look up (or with `ShmFlags::CREATE`, create) the shared-memory segment
+/// named `key`, returning its id.
+pub fn shm_get(key: usize, size: usize, flags: ShmFlags) -> isize {
+    sys_shmget(key, size, flags.bits)
+}
+
+/// Map segment `id` at `start`, or wherever the kernel likes if `start` is 0.
+/// Returns the mapped address. Attachments are inherited across `fork`.
+pub fn shm_attach(id: usize, start: usize, flags: ShmFlags) -> isize {
+    sys_shmat(id, start, flags.bits)
+}
+
+pub fn shm_detach(start: usize) -> isize {
+    sys_shmdt(start)
+}
+
+/// Destroy segment `id` once the last process has detached it.
+pub fn shm_remove(id: usize) -> isize {
+    sys_shmctl(id, SHM_RMID, 0)
+}
+
 pub fn sbrk(size: i32) -> isize {
     sys_sbrk(size)
 }
@@ -290,10 +660,146 @@ pub fn spawn(path: &str) -> isize {
 pub fn dup(fd: usize) -> isize {
     sys_dup(fd)
 }
+
+/// Make `new_fd` refer to the same file as `old_fd`, closing whatever
+/// `new_fd` held before, in one step. Returns `new_fd`. If the two are equal,
+/// only checks that `old_fd` is open. Needs `SYSCALL_DUP3`, so programs that
+/// must run on every kernel stick to `close` followed by `dup`.
+pub fn dup2(old_fd: usize, new_fd: usize) -> isize {
+    if old_fd == new_fd {
+        return match sys_fcntl(old_fd, F_GETFD, 0) {
+            ret if ret < 0 => ret,
+            _ => new_fd as isize,
+        };
+    }
+    dup3(old_fd, new_fd, OpenFlags::empty())
+}
+
+/// Like `dup2`, but fails if the fds are equal and accepts
+/// `OpenFlags::CLOEXEC` for `new_fd`.
+pub fn dup3(old_fd: usize, new_fd: usize, flags: OpenFlags) -> isize {
+    if new_fd == STDOUT {
+        flush();
+    }
+    sys_dup3(old_fd, new_fd, flags.bits)
+}
 pub fn pipe(pipe_fd: &mut [usize]) -> isize {
     sys_pipe(pipe_fd)
 }
 
+/// Pipe writes of at most this many bytes are atomic: they are never
+/// interleaved with data from other writers.
+pub const PIPE_BUF: usize = 512;
+
+/// Like `pipe`, with `OpenFlags::NONBLOCK` and/or `OpenFlags::CLOEXEC`
+/// applied to both ends.
+pub fn pipe2(pipe_fd: &mut [usize], flags: OpenFlags) -> isize {
+    sys_pipe2(pipe_fd, flags.bits)
+}
+
+const F_GETFD: usize = 1;
+const F_GETFL: usize = 3;
+const F_SETFL: usize = 4;
+
+const FD_CLOEXEC: isize = 1;
+
+/// Whether `fd` is closed on `exec`, or `None` if it is not open.
+pub fn get_cloexec(fd: usize) -> Option<bool> {
+    match sys_fcntl(fd, F_GETFD, 0) {
+        ret if ret < 0 => None,
+        ret => Some(ret & FD_CLOEXEC != 0),
+    }
+}
+
+/// The status flags of `fd`, e.g. `OpenFlags::NONBLOCK`, or `None` if it is not open.
+pub fn get_fd_flags(fd: usize) -> Option<OpenFlags> {
+    match sys_fcntl(fd, F_GETFL, 0) {
+        ret if ret < 0 => None,
+        ret => Some(OpenFlags::from_bits_truncate(ret as u32)),
+    }
+}
+
+/// Replace the status flags of `fd`. Only `APPEND` and `NONBLOCK` can change.
+pub fn set_fd_flags(fd: usize, flags: OpenFlags) -> isize {
+    sys_fcntl(fd, F_SETFL, flags.bits as usize)
+}
+
+/// Switch `fd`, which may be a pipe end or the console, in or out of
+/// non-blocking mode.
+pub fn set_nonblocking(fd: usize, nonblocking: bool) -> isize {
+    match get_fd_flags(fd) {
+        Some(mut flags) => {
+            flags.set(OpenFlags::NONBLOCK, nonblocking);
+            set_fd_flags(fd, flags)
+        }
+        None => -1,
+    }
+}
+
+bitflags! {
+    pub struct PollEvents: i16 {
+        /// data can be read without blocking, or a pipe's writers are gone
+        const IN = 0x001;
+        const PRI = 0x002;
+        /// data can be written without blocking
+        const OUT = 0x004;
+        /// only in `revents`: error condition, e.g. a pipe's readers are gone
+        const ERR = 0x008;
+        /// only in `revents`: the other end hung up
+        const HUP = 0x010;
+        /// only in `revents`: `fd` is not open
+        const NVAL = 0x020;
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct PollFd {
+    /// negative fds are skipped and get empty `revents`
+    pub fd: i32,
+    pub events: PollEvents,
+    pub revents: PollEvents,
+}
+
+impl PollFd {
+    pub fn new(fd: usize, events: PollEvents) -> Self {
+        Self {
+            fd: fd as i32,
+            events,
+            revents: PollEvents::empty(),
+        }
+    }
+}
+
+/// Wait until one of `fds` is ready or `timeout_ms` milliseconds pass; a
+/// negative timeout waits forever and 0 returns at once. Fills in `revents`
+/// and returns the number of entries with non-empty `revents`.
+pub fn poll(fds: &mut [PollFd], timeout_ms: isize) -> isize {
+    let timeout = if timeout_ms < 0 {
+        None
+    } else {
+        Some(time::Duration::from_millis(timeout_ms as u64))
+    };
+    ppoll(fds, timeout, None)
+}
+
+/// Like `poll`, with a finer timeout and optionally `sigmask` replacing the
+/// signal mask for the duration of the call.
+pub fn ppoll(
+    fds: &mut [PollFd],
+    timeout: Option<time::Duration>,
+    sigmask: Option<SignalFlags>,
+) -> isize {
+    let timeout = timeout.map(TimeSpec::from);
+    let timeout_ptr = timeout
+        .as_ref()
+        .map_or(core::ptr::null(), |ts| ts as *const TimeSpec);
+    // like Linux, the kernel expects a whole 64-bit signal set
+    let mask = sigmask.map(|m| m.bits as u32 as u64);
+    let mask_ptr = mask.as_ref().map_or(core::ptr::null(), |m| m as *const u64);
+    sys_ppoll(fds, timeout_ptr, mask_ptr, core::mem::size_of::<u64>())
+}
+
 pub fn trace(request: TraceRequest, id: usize, data: usize) -> isize {
     sys_trace(request as usize, id, data)
 }
@@ -313,6 +819,12 @@ pub fn count_syscall(id: usize) -> isize {
     trace(TraceRequest::Syscall, id, 0)
 }
 
+/// Fill `info` with the current frame usage. Meant for leak checks: compare
+/// `free_frames` before and after a workload that should release everything.
+pub fn meminfo(info: &mut MemInfo) -> isize {
+    sys_meminfo(info)
+}
+
 pub fn thread_create(entry: usize, arg: usize) -> isize {
     sys_thread_create(entry, arg)
 }
@@ -321,7 +833,7 @@ pub fn gettid() -> isize {
 }
 pub fn waittid(tid: usize) -> isize {
     loop {
-        match sys_waittid(tid) {
+        match sys_waittid(tid, WaitFlags::empty().bits) {
             -2 => {
                 yield_();
             }
@@ -329,6 +841,9 @@ pub fn waittid(tid: usize) -> isize {
         }
     }
 }
+pub fn waittid_with_flags(tid: usize, flags: WaitFlags) -> isize {
+    sys_waittid(tid, flags.bits)
+}
 
 pub fn mutex_create() -> isize {
     sys_mutex_create(false)
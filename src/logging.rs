@@ -0,0 +1,100 @@
+//! Backend for the `log` crate, writing coloured records to stderr.
+//!
+//! The default level is taken from the `LOG` environment variable when
+//! user_lib is *built*, since the kernels pass no environment to programs, and
+//! is `warn` if it is unset. To change the level at run time, a program opts in
+//! with `parse_args`, which honours a `--log=<level>` argument. Records are
+//! tagged with the caller's pid, and optionally its tid (see `set_tags`). Those
+//! tags cost a syscall each, so don't enable logging on kernels without
+//! `getpid`/`gettid`.
+
+use alloc::string::String;
+use core::fmt::Write;
+use core::sync::atomic::{AtomicU32, Ordering};
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+use super::{getpid, gettid, write, STDERR};
+
+const ARG_PREFIX: &str = "--log=";
+
+bitflags! {
+    pub struct LogTags: u32 {
+        const PID = 1 << 0;
+        const TID = 1 << 1;
+    }
+}
+
+static TAGS: AtomicU32 = AtomicU32::new(LogTags::PID.bits());
+
+struct SimpleLogger;
+
+impl Log for SimpleLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let color = match record.level() {
+            Level::Error => 31, // Red
+            Level::Warn => 93,  // BrightYellow
+            Level::Info => 34,  // Blue
+            Level::Debug => 32, // Green
+            Level::Trace => 90, // BrightBlack
+        };
+        let tags = tags();
+        // 整行格式化后一次写出，避免并发进程的输出在行内交错
+        let mut line = String::new();
+        let _ = write!(line, "\u{1B}[{}m[{:>5}]", color, record.level());
+        if tags.contains(LogTags::PID) {
+            let _ = write!(line, "[pid {}]", getpid());
+        }
+        if tags.contains(LogTags::TID) {
+            let _ = write!(line, "[tid {}]", gettid());
+        }
+        let _ = writeln!(line, " {}\u{1B}[0m", record.args());
+        write(STDERR, line.as_bytes());
+    }
+
+    fn flush(&self) {}
+}
+
+/// Install the logger at the level given by `LOG` at build time. Called
+/// before `main`.
+pub fn init() {
+    static LOGGER: SimpleLogger = SimpleLogger;
+    // only fails if a logger is already installed, which is fine
+    let _ = log::set_logger(&LOGGER);
+    let level = match option_env!("LOG") {
+        Some(s) => s.parse().unwrap_or(LevelFilter::Warn),
+        None => LevelFilter::Warn,
+    };
+    log::set_max_level(level);
+}
+
+/// Take the level from the last `--log=<level>` argument in `args`, if any.
+/// The arguments are left in place, so callers that look at the rest of argv
+/// have to skip them.
+pub fn parse_args(args: &[&str]) {
+    for arg in args {
+        if let Some(level) = arg.strip_prefix(ARG_PREFIX) {
+            if let Ok(level) = level.parse() {
+                log::set_max_level(level);
+            }
+        }
+    }
+}
+
+pub fn set_level(level: LevelFilter) {
+    log::set_max_level(level);
+}
+
+pub fn set_tags(tags: LogTags) {
+    TAGS.store(tags.bits(), Ordering::Relaxed);
+}
+
+pub fn tags() -> LogTags {
+    LogTags::from_bits_truncate(TAGS.load(Ordering::Relaxed))
+}
@@ -0,0 +1,431 @@
+//! Typed messages over the mailbox syscalls.
+//!
+//! A `Mailbox` sends values implementing `Message` to other processes and
+//! receives them back by type. Every value is encoded little endian with a
+//! fixed layout, prefixed by a small header carrying the message kind, a
+//! correlation id and fragment numbering, so payloads larger than
+//! `MAX_MSG_LEN` are split on send and reassembled on receive. Requests and
+//! replies are paired by correlation id, see `Mailbox::call` and
+//! `Mailbox::reply`.
+
+use alloc::collections::vec_deque::VecDeque;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::convert::TryInto;
+
+use super::{mail_read_with_flags, mail_status, mail_write, yield_, MailFlags, MailStatus};
+
+/// Largest message the kernel delivers in one piece; longer writes are cut.
+pub const MAX_MSG_LEN: usize = 256;
+/// Largest encoded value `send` accepts and `recv` reassembles, so that a
+/// corrupt header cannot exhaust the small user heap.
+pub const MAX_PAYLOAD_LEN: usize = 4096;
+
+const HEADER_LEN: usize = 16;
+const MAX_FRAGMENT_PAYLOAD: usize = MAX_MSG_LEN - HEADER_LEN;
+const FLAG_REPLY: u8 = 1;
+/// How often a write to a full mailbox is retried before giving up.
+const WRITE_RETRIES: usize = 1000;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum MailError {
+    /// the destination process does not exist
+    NoSuchProcess,
+    /// a fragment or payload could not be decoded
+    Malformed,
+    /// the payload is longer than `MAX_PAYLOAD_LEN`
+    TooLarge,
+    /// the destination mailbox stayed full
+    Full,
+    /// `mail_read` failed with this error code
+    Read(isize),
+}
+
+/// A value with a fixed little-endian wire layout.
+pub trait Wire: Sized {
+    fn encode(&self, out: &mut Vec<u8>);
+    /// Decode a value from the front of `input`, advancing it past the value.
+    fn decode(input: &mut &[u8]) -> Option<Self>;
+}
+
+/// A top-level message. `KIND` tells message types apart on the wire and must
+/// be unique among the types exchanged by a group of processes.
+pub trait Message: Wire {
+    const KIND: u16;
+}
+
+fn take<'a>(input: &mut &'a [u8], len: usize) -> Option<&'a [u8]> {
+    if input.len() < len {
+        return None;
+    }
+    let (head, tail) = input.split_at(len);
+    *input = tail;
+    Some(head)
+}
+
+macro_rules! impl_wire_int {
+    ($($t:ty),*) => {
+        $(
+            impl Wire for $t {
+                fn encode(&self, out: &mut Vec<u8>) {
+                    out.extend_from_slice(&self.to_le_bytes());
+                }
+
+                fn decode(input: &mut &[u8]) -> Option<Self> {
+                    let bytes = take(input, core::mem::size_of::<$t>())?;
+                    Some(<$t>::from_le_bytes(bytes.try_into().ok()?))
+                }
+            }
+        )*
+    };
+}
+
+impl_wire_int!(u8, u16, u32, u64, i8, i16, i32, i64);
+
+/// Always 8 bytes on the wire.
+impl Wire for usize {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (*self as u64).encode(out);
+    }
+
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        u64::decode(input).map(|v| v as usize)
+    }
+}
+
+impl Wire for bool {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(*self as u8);
+    }
+
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        match u8::decode(input)? {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        }
+    }
+}
+
+impl Wire for () {
+    fn encode(&self, _out: &mut Vec<u8>) {}
+
+    fn decode(_input: &mut &[u8]) -> Option<Self> {
+        Some(())
+    }
+}
+
+/// A `u32` length followed by the bytes.
+impl Wire for Vec<u8> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (self.len() as u32).encode(out);
+        out.extend_from_slice(self);
+    }
+
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        let len = u32::decode(input)? as usize;
+        take(input, len).map(Vec::from)
+    }
+}
+
+impl Wire for String {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (self.len() as u32).encode(out);
+        out.extend_from_slice(self.as_bytes());
+    }
+
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        String::from_utf8(Vec::<u8>::decode(input)?).ok()
+    }
+}
+
+impl<A: Wire, B: Wire> Wire for (A, B) {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.0.encode(out);
+        self.1.encode(out);
+    }
+
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        Some((A::decode(input)?, B::decode(input)?))
+    }
+}
+
+impl<A: Wire, B: Wire, C: Wire> Wire for (A, B, C) {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.0.encode(out);
+        self.1.encode(out);
+        self.2.encode(out);
+    }
+
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        Some((A::decode(input)?, B::decode(input)?, C::decode(input)?))
+    }
+}
+
+/// Fragment header: `kind: u16, flags: u8, pad: u8, id: u32, index: u16,
+/// count: u16, total_len: u32`.
+#[derive(Copy, Clone, Debug)]
+struct Header {
+    kind: u16,
+    flags: u8,
+    id: u32,
+    index: u16,
+    count: u16,
+    total_len: u32,
+}
+
+impl Header {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.kind.encode(out);
+        self.flags.encode(out);
+        0u8.encode(out);
+        self.id.encode(out);
+        self.index.encode(out);
+        self.count.encode(out);
+        self.total_len.encode(out);
+    }
+
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        let kind = u16::decode(input)?;
+        let flags = u8::decode(input)?;
+        u8::decode(input)?;
+        Some(Self {
+            kind,
+            flags,
+            id: u32::decode(input)?,
+            index: u16::decode(input)?,
+            count: u16::decode(input)?,
+            total_len: u32::decode(input)?,
+        })
+    }
+}
+
+/// A complete message that has not been decoded yet.
+struct Envelope {
+    sender: usize,
+    kind: u16,
+    id: u32,
+    reply: bool,
+    payload: Vec<u8>,
+}
+
+/// A message being reassembled from its fragments.
+struct Partial {
+    sender: usize,
+    header: Header,
+    /// index of the fragment expected next
+    next: u16,
+    payload: Vec<u8>,
+}
+
+impl Partial {
+    /// Whether `header` is the next fragment of this message.
+    fn accepts(&self, header: &Header) -> bool {
+        header.index == self.next
+            && header.kind == self.header.kind
+            && header.count == self.header.count
+            && header.total_len == self.header.total_len
+    }
+}
+
+/// A decoded message together with where it came from.
+#[derive(Debug)]
+pub struct Received<T> {
+    pub sender: usize,
+    /// correlation id, pass the whole `Received` to `Mailbox::reply` to answer
+    pub id: u32,
+    pub msg: T,
+}
+
+/// The calling process's view of the mailbox system. Only one `Mailbox`
+/// should receive in a process, otherwise they steal each other's fragments.
+pub struct Mailbox {
+    next_id: u32,
+    partial: Vec<Partial>,
+    ready: VecDeque<Envelope>,
+}
+
+impl Default for Mailbox {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Mailbox {
+    pub fn new() -> Self {
+        Self {
+            next_id: 1,
+            partial: Vec::new(),
+            ready: VecDeque::new(),
+        }
+    }
+
+    /// Send `msg` to `pid`, waiting a while if its mailbox is full. Returns
+    /// the correlation id of the message.
+    pub fn send<T: Message>(&mut self, pid: usize, msg: &T) -> Result<u32, MailError> {
+        let id = self.next_id;
+        self.next_id = self.next_id.wrapping_add(1).max(1);
+        self.send_with(pid, T::KIND, 0, id, msg)?;
+        Ok(id)
+    }
+
+    /// Answer `request` with `msg`. The reply carries the request's id.
+    pub fn reply<R, T: Message>(
+        &mut self,
+        request: &Received<R>,
+        msg: &T,
+    ) -> Result<(), MailError> {
+        self.send_with(request.sender, T::KIND, FLAG_REPLY, request.id, msg)
+    }
+
+    /// Send `msg` to `pid` and wait for the reply to it. Other messages that
+    /// arrive meanwhile are kept for later `recv` calls.
+    pub fn call<T: Message, R: Message>(&mut self, pid: usize, msg: &T) -> Result<R, MailError> {
+        let id = self.send(pid, msg)?;
+        let env = self.wait_for(|env| {
+            env.reply && env.id == id && env.sender == pid && env.kind == R::KIND
+        })?;
+        decode_payload(&env.payload)
+    }
+
+    /// Wait for the next message of type `T` that is not a reply. Other
+    /// messages that arrive meanwhile are kept for later calls.
+    pub fn recv<T: Message>(&mut self) -> Result<Received<T>, MailError> {
+        let env = self.wait_for(|env| !env.reply && env.kind == T::KIND)?;
+        Ok(Received {
+            sender: env.sender,
+            id: env.id,
+            msg: decode_payload(&env.payload)?,
+        })
+    }
+
+    fn send_with<T: Wire>(
+        &mut self,
+        pid: usize,
+        kind: u16,
+        flags: u8,
+        id: u32,
+        msg: &T,
+    ) -> Result<(), MailError> {
+        let mut payload = Vec::new();
+        msg.encode(&mut payload);
+        if payload.len() > MAX_PAYLOAD_LEN {
+            return Err(MailError::TooLarge);
+        }
+        let count = fragment_count(payload.len());
+        let mut fragment = Vec::with_capacity(MAX_MSG_LEN);
+        for index in 0..count {
+            let start = index * MAX_FRAGMENT_PAYLOAD;
+            let end = payload.len().min(start + MAX_FRAGMENT_PAYLOAD);
+            fragment.clear();
+            Header {
+                kind,
+                flags,
+                id,
+                index: index as u16,
+                count: count as u16,
+                total_len: payload.len() as u32,
+            }
+            .encode(&mut fragment);
+            fragment.extend_from_slice(&payload[start..end]);
+            write_fragment(pid, &fragment)?;
+        }
+        Ok(())
+    }
+
+    fn wait_for(&mut self, matches: impl Fn(&Envelope) -> bool) -> Result<Envelope, MailError> {
+        if let Some(pos) = self.ready.iter().position(&matches) {
+            return Ok(self.ready.remove(pos).unwrap());
+        }
+        loop {
+            let env = self.next_envelope()?;
+            if matches(&env) {
+                return Ok(env);
+            }
+            self.ready.push_back(env);
+        }
+    }
+
+    /// Read fragments until one completes a message.
+    fn next_envelope(&mut self) -> Result<Envelope, MailError> {
+        let mut buf = [0u8; MAX_MSG_LEN];
+        loop {
+            let (len, sender) = mail_read_with_flags(&mut buf, MailFlags::BLOCK);
+            if len < 0 {
+                return Err(MailError::Read(len));
+            }
+            let mut input = &buf[..len as usize];
+            let header = Header::decode(&mut input).ok_or(MailError::Malformed)?;
+            let total_len = header.total_len as usize;
+            if total_len > MAX_PAYLOAD_LEN || header.count as usize != fragment_count(total_len) {
+                return Err(MailError::Malformed);
+            }
+            let reply = header.flags & FLAG_REPLY != 0;
+            // fragments of one message come from one sender, in order; a
+            // message with a gap, a repeat or a changed header is dropped
+            let pos = self.partial.iter().position(|p| {
+                p.sender == sender && p.header.id == header.id && p.header.flags == header.flags
+            });
+            let mut partial = match pos {
+                Some(pos) => self.partial.swap_remove(pos),
+                None => Partial {
+                    sender,
+                    header,
+                    next: 0,
+                    payload: Vec::with_capacity(total_len),
+                },
+            };
+            if !partial.accepts(&header) || partial.payload.len() + input.len() > total_len {
+                return Err(MailError::Malformed);
+            }
+            partial.payload.extend_from_slice(input);
+            partial.next += 1;
+            if partial.next < header.count {
+                // every fragment but the last one is full
+                if input.len() != MAX_FRAGMENT_PAYLOAD {
+                    return Err(MailError::Malformed);
+                }
+                self.partial.push(partial);
+                continue;
+            }
+            if partial.payload.len() != total_len {
+                return Err(MailError::Malformed);
+            }
+            return Ok(Envelope {
+                sender,
+                kind: header.kind,
+                id: header.id,
+                reply,
+                payload: partial.payload,
+            });
+        }
+    }
+}
+
+fn decode_payload<T: Wire>(payload: &[u8]) -> Result<T, MailError> {
+    let mut input = payload;
+    match T::decode(&mut input) {
+        Some(msg) if input.is_empty() => Ok(msg),
+        _ => Err(MailError::Malformed),
+    }
+}
+
+/// Number of fragments a payload of `len` bytes is split into.
+fn fragment_count(len: usize) -> usize {
+    len.max(1).div_ceil(MAX_FRAGMENT_PAYLOAD)
+}
+
+fn write_fragment(pid: usize, fragment: &[u8]) -> Result<(), MailError> {
+    for _ in 0..WRITE_RETRIES {
+        if mail_write(pid, fragment) >= 0 {
+            return Ok(());
+        }
+        // a full mailbox and a missing process both fail the write
+        let mut status = MailStatus::new();
+        if mail_status(pid, &mut status) < 0 {
+            return Err(MailError::NoSuchProcess);
+        }
+        yield_();
+    }
+    Err(MailError::Full)
+}
@@ -0,0 +1,248 @@
+//! BSD-style sockets.
+//!
+//! Supports Unix-domain sockets bound to filesystem paths and IPv4 TCP/UDP
+//! sockets. Socket fds work with `read`, `write`, `close`, `dup2` and `poll`
+//! like pipes do. Addresses are passed through the `SockAddr` trait, which
+//! gives their raw `sockaddr` bytes.
+
+use core::mem::size_of;
+
+use super::{
+    sys_accept, sys_bind, sys_connect, sys_getpeername, sys_getsockname, sys_listen, sys_recvfrom,
+    sys_sendto, sys_shutdown, sys_socket,
+};
+
+pub const AF_UNIX: u16 = 1;
+pub const AF_INET: u16 = 2;
+
+/// Reliable, ordered byte stream between two connected sockets.
+pub const SOCK_STREAM: usize = 1;
+/// Unreliable messages whose boundaries are preserved.
+pub const SOCK_DGRAM: usize = 2;
+
+bitflags! {
+    pub struct MsgFlags: u32 {
+        /// return queued data without removing it
+        const PEEK = 0x2;
+        /// fail with `EAGAIN` instead of blocking
+        const DONTWAIT = 0x40;
+        /// for streams, block until the whole buffer is filled
+        const WAITALL = 0x100;
+        /// return `-EPIPE` instead of raising `SIGPIPE` on a closed peer
+        const NOSIGNAL = 0x4000;
+    }
+}
+
+/// Which directions `shutdown` closes.
+#[repr(usize)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Shutdown {
+    Read = 0,
+    Write = 1,
+    Both = 2,
+}
+
+/// A socket address with a C `sockaddr_*` layout.
+pub trait SockAddr {
+    fn as_bytes(&self) -> &[u8];
+    fn as_bytes_mut(&mut self) -> &mut [u8];
+}
+
+const UNIX_PATH_MAX: usize = 108;
+
+/// `sockaddr_un`: a NUL-terminated filesystem path.
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct SockAddrUn {
+    pub family: u16,
+    pub path: [u8; UNIX_PATH_MAX],
+}
+
+impl SockAddrUn {
+    /// `None` if `path` is too long. A trailing `\0` is optional.
+    pub fn new(path: &str) -> Option<Self> {
+        let path = path.trim_end_matches('\0').as_bytes();
+        if path.len() >= UNIX_PATH_MAX {
+            return None;
+        }
+        let mut addr = Self::unnamed();
+        addr.path[..path.len()].copy_from_slice(path);
+        Some(addr)
+    }
+
+    /// The address of a socket that was never bound.
+    pub fn unnamed() -> Self {
+        Self {
+            family: AF_UNIX,
+            path: [0; UNIX_PATH_MAX],
+        }
+    }
+
+    /// The path without its terminating NUL.
+    pub fn path(&self) -> &str {
+        let len = self
+            .path
+            .iter()
+            .position(|&b| b == 0)
+            .unwrap_or(UNIX_PATH_MAX);
+        core::str::from_utf8(&self.path[..len]).unwrap_or("")
+    }
+}
+
+impl SockAddr for SockAddrUn {
+    fn as_bytes(&self) -> &[u8] {
+        unsafe { core::slice::from_raw_parts(self as *const _ as *const u8, size_of::<Self>()) }
+    }
+
+    fn as_bytes_mut(&mut self) -> &mut [u8] {
+        unsafe { core::slice::from_raw_parts_mut(self as *mut _ as *mut u8, size_of::<Self>()) }
+    }
+}
+
+/// An IPv4 address, most significant octet first.
+#[repr(C)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub struct Ipv4Addr(pub [u8; 4]);
+
+impl Ipv4Addr {
+    pub const LOCALHOST: Ipv4Addr = Ipv4Addr([127, 0, 0, 1]);
+    /// binds to every local address
+    pub const UNSPECIFIED: Ipv4Addr = Ipv4Addr([0, 0, 0, 0]);
+
+    pub const fn new(a: u8, b: u8, c: u8, d: u8) -> Self {
+        Ipv4Addr([a, b, c, d])
+    }
+
+    pub fn is_loopback(&self) -> bool {
+        self.0[0] == 127
+    }
+}
+
+/// `sockaddr_in`. The port and address are stored in network byte order, use
+/// the accessors to read them.
+#[repr(C)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct SockAddrIn {
+    pub family: u16,
+    port_be: u16,
+    pub addr: Ipv4Addr,
+    zero: [u8; 8],
+}
+
+const _: () = assert!(size_of::<SockAddrIn>() == 16);
+
+impl SockAddrIn {
+    /// Port 0 lets the kernel pick a free port when binding.
+    pub fn new(addr: Ipv4Addr, port: u16) -> Self {
+        Self {
+            family: AF_INET,
+            port_be: port.to_be(),
+            addr,
+            zero: [0; 8],
+        }
+    }
+
+    pub fn port(&self) -> u16 {
+        u16::from_be(self.port_be)
+    }
+
+    pub fn ip(&self) -> Ipv4Addr {
+        self.addr
+    }
+}
+
+impl SockAddr for SockAddrIn {
+    fn as_bytes(&self) -> &[u8] {
+        unsafe { core::slice::from_raw_parts(self as *const _ as *const u8, size_of::<Self>()) }
+    }
+
+    fn as_bytes_mut(&mut self) -> &mut [u8] {
+        unsafe { core::slice::from_raw_parts_mut(self as *mut _ as *mut u8, size_of::<Self>()) }
+    }
+}
+
+/// Zero `addr` and pass it to `f` as a buffer the kernel fills in. The kernel
+/// may write only part of it, e.g. just the family of an unbound `AF_UNIX`
+/// peer, so nothing stale may be left in the rest.
+fn with_addr_out(addr: &mut impl SockAddr, f: impl FnOnce(*mut u8, &mut u32) -> isize) -> isize {
+    let bytes = addr.as_bytes_mut();
+    bytes.fill(0);
+    let mut len = bytes.len() as u32;
+    f(bytes.as_mut_ptr(), &mut len)
+}
+
+/// Create a socket, returning its fd.
+pub fn socket(domain: u16, ty: usize) -> isize {
+    sys_socket(domain as usize, ty, 0)
+}
+
+/// Give the socket `fd` the address `addr`. For `AF_UNIX` this creates the
+/// path, which must not exist yet.
+pub fn bind(fd: usize, addr: &impl SockAddr) -> isize {
+    sys_bind(fd, addr.as_bytes())
+}
+
+/// Mark the stream socket `fd` as accepting connections, queueing at most
+/// `backlog` of them until `accept`.
+pub fn listen(fd: usize, backlog: usize) -> isize {
+    sys_listen(fd, backlog)
+}
+
+/// Wait for a connection on the listening socket `fd`, returning a new fd
+/// connected to the peer.
+pub fn accept(fd: usize) -> isize {
+    sys_accept(fd, core::ptr::null_mut(), core::ptr::null_mut())
+}
+
+/// Like `accept`, also storing the peer's address in `peer`.
+pub fn accept_from(fd: usize, peer: &mut impl SockAddr) -> isize {
+    with_addr_out(peer, |addr, len| sys_accept(fd, addr, len))
+}
+
+/// Connect `fd` to `addr`. For datagram sockets this only sets the default
+/// destination.
+pub fn connect(fd: usize, addr: &impl SockAddr) -> isize {
+    sys_connect(fd, addr.as_bytes())
+}
+
+pub fn send(fd: usize, buf: &[u8], flags: MsgFlags) -> isize {
+    sys_sendto(fd, buf, flags.bits, &[])
+}
+
+pub fn recv(fd: usize, buf: &mut [u8], flags: MsgFlags) -> isize {
+    sys_recvfrom(
+        fd,
+        buf,
+        flags.bits,
+        core::ptr::null_mut(),
+        core::ptr::null_mut(),
+    )
+}
+
+/// Send one datagram to `addr`.
+pub fn sendto(fd: usize, buf: &[u8], flags: MsgFlags, addr: &impl SockAddr) -> isize {
+    sys_sendto(fd, buf, flags.bits, addr.as_bytes())
+}
+
+/// Receive one datagram, storing its sender's address in `from`. Bytes that
+/// do not fit in `buf` are discarded.
+pub fn recvfrom(fd: usize, buf: &mut [u8], flags: MsgFlags, from: &mut impl SockAddr) -> isize {
+    with_addr_out(from, |addr, len| {
+        sys_recvfrom(fd, buf, flags.bits, addr, len)
+    })
+}
+
+/// Store the local address of `fd` in `addr`, e.g. to learn the port the
+/// kernel picked for a bind to port 0.
+pub fn getsockname(fd: usize, addr: &mut impl SockAddr) -> isize {
+    with_addr_out(addr, |addr, len| sys_getsockname(fd, addr, len))
+}
+
+/// Store the address of the peer `fd` is connected to in `addr`.
+pub fn getpeername(fd: usize, addr: &mut impl SockAddr) -> isize {
+    with_addr_out(addr, |addr, len| sys_getpeername(fd, addr, len))
+}
+
+pub fn shutdown(fd: usize, how: Shutdown) -> isize {
+    sys_shutdown(fd, how as usize)
+}
@@ -0,0 +1,109 @@
+//! Kernel entropy and a small seedable PRNG.
+//!
+//! `getrandom` fills a buffer from the kernel's entropy source. `Rng` is a
+//! PCG32 generator: given the same seed it always produces the same sequence,
+//! so a test that prints its seed can be rerun with `--seed=N` to reproduce a
+//! failing workload.
+
+use super::{get_time_us, getpid, sys_getrandom};
+
+bitflags! {
+    pub struct GetRandomFlags: u32 {
+        /// fail with EAGAIN instead of blocking until the pool is initialized
+        const NONBLOCK = 1;
+        /// draw from the blocking pool
+        const RANDOM = 2;
+    }
+}
+
+/// Fill `buf` with random bytes from the kernel. Returns the number of bytes
+/// written, or a negative error.
+pub fn getrandom(buf: &mut [u8], flags: GetRandomFlags) -> isize {
+    sys_getrandom(buf, flags.bits)
+}
+
+const MULTIPLIER: u64 = 6364136223846793005;
+const INCREMENT: u64 = 1442695040888963407;
+
+/// PCG32 (XSH RR) generator. Not suitable for anything security related.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    seed: u64,
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        let mut rng = Self { seed, state: 0 };
+        rng.step();
+        rng.state = rng.state.wrapping_add(seed);
+        rng.step();
+        rng
+    }
+
+    /// Seed from `getrandom`, falling back to the clock and pid if the kernel
+    /// has no entropy source.
+    pub fn from_entropy() -> Self {
+        let mut buf = [0u8; 8];
+        let seed = if getrandom(&mut buf, GetRandomFlags::NONBLOCK) == buf.len() as isize {
+            u64::from_le_bytes(buf)
+        } else {
+            (get_time_us() as u64) ^ ((getpid() as u64) << 48)
+        };
+        Self::new(seed)
+    }
+
+    /// Seed from a `--seed=N` argument, or from entropy for `--seed=random`.
+    /// Without either, use `default` so runs stay reproducible and never need
+    /// `getrandom`. Panics if the seed is neither a number nor `random`.
+    pub fn from_args(argv: &[&str], default: u64) -> Self {
+        match argv.iter().find_map(|arg| arg.strip_prefix("--seed=")) {
+            Some("random") => Self::from_entropy(),
+            Some(seed) => match seed.parse() {
+                Ok(seed) => Self::new(seed),
+                Err(_) => panic!("invalid --seed value {:?}", seed),
+            },
+            None => Self::new(default),
+        }
+    }
+
+    /// The seed this generator was created with.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    fn step(&mut self) {
+        self.state = self.state.wrapping_mul(MULTIPLIER).wrapping_add(INCREMENT);
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        let old = self.state;
+        self.step();
+        let xorshifted = (((old >> 18) ^ old) >> 27) as u32;
+        xorshifted.rotate_right((old >> 59) as u32)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        ((self.next_u32() as u64) << 32) | self.next_u32() as u64
+    }
+
+    /// A uniformly distributed value in `0..bound`. `bound` must be non-zero.
+    pub fn below(&mut self, bound: u32) -> u32 {
+        assert!(bound > 0);
+        // reject the low values that would bias the modulo
+        let threshold = bound.wrapping_neg() % bound;
+        loop {
+            let r = self.next_u32();
+            if r >= threshold {
+                return r % bound;
+            }
+        }
+    }
+
+    pub fn fill_bytes(&mut self, buf: &mut [u8]) {
+        for chunk in buf.chunks_mut(4) {
+            let bytes = self.next_u32().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+}
@@ -0,0 +1,287 @@
+//! A small line editor for interactive programs.
+//!
+//! Supports in-line insertion, cursor movement (arrow keys, Home/End,
+//! Ctrl-A/E/B/F), deletion (Backspace, Delete, Ctrl-W, Ctrl-U, Ctrl-K) and a
+//! bounded history browsed with the up/down arrows. Only printable ASCII is
+//! accepted into the line.
+
+use alloc::collections::vec_deque::VecDeque;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use super::{flush, read, STDIN};
+
+const CTRL_A: u8 = 0x01;
+const CTRL_B: u8 = 0x02;
+const CTRL_D: u8 = 0x04;
+const CTRL_E: u8 = 0x05;
+const CTRL_F: u8 = 0x06;
+const BS: u8 = 0x08;
+const LF: u8 = 0x0a;
+const CTRL_K: u8 = 0x0b;
+const CR: u8 = 0x0d;
+const CTRL_N: u8 = 0x0e;
+const CTRL_P: u8 = 0x10;
+const CTRL_U: u8 = 0x15;
+const CTRL_W: u8 = 0x17;
+const ESC: u8 = 0x1b;
+const DL: u8 = 0x7f;
+
+/// A decoded keystroke.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum Key {
+    Char(u8),
+    Enter,
+    Backspace,
+    Delete,
+    Left,
+    Right,
+    Home,
+    End,
+    Up,
+    Down,
+    DeleteWord,
+    KillToStart,
+    KillToEnd,
+    Eof,
+    Ignore,
+}
+
+fn read_byte() -> Option<u8> {
+    let mut c = [0u8; 1];
+    match read(STDIN, &mut c) {
+        1 => Some(c[0]),
+        _ => None,
+    }
+}
+
+/// Decode the rest of an escape sequence after `ESC`:
+/// `ESC [ A..D`, `ESC [ H/F`, `ESC O H/F` and `ESC [ n ~`.
+fn read_escape(next: &mut impl FnMut() -> Option<u8>) -> Key {
+    let kind = match next() {
+        Some(c) => c,
+        None => return Key::Eof,
+    };
+    if kind != b'[' && kind != b'O' {
+        return Key::Ignore;
+    }
+    let mut param = 0u32;
+    loop {
+        let c = match next() {
+            Some(c) => c,
+            None => return Key::Eof,
+        };
+        match c {
+            b'0'..=b'9' => param = param * 10 + (c - b'0') as u32,
+            b'A' => return Key::Up,
+            b'B' => return Key::Down,
+            b'C' => return Key::Right,
+            b'D' => return Key::Left,
+            b'H' => return Key::Home,
+            b'F' => return Key::End,
+            b'~' => {
+                return match param {
+                    1 | 7 => Key::Home,
+                    3 => Key::Delete,
+                    4 | 8 => Key::End,
+                    _ => Key::Ignore,
+                }
+            }
+            b';' => {}
+            _ => return Key::Ignore,
+        }
+    }
+}
+
+fn read_key(next: &mut impl FnMut() -> Option<u8>) -> Key {
+    let c = match next() {
+        Some(c) => c,
+        None => return Key::Eof,
+    };
+    match c {
+        LF | CR => Key::Enter,
+        BS | DL => Key::Backspace,
+        CTRL_A => Key::Home,
+        CTRL_E => Key::End,
+        CTRL_B => Key::Left,
+        CTRL_F => Key::Right,
+        CTRL_P => Key::Up,
+        CTRL_N => Key::Down,
+        CTRL_W => Key::DeleteWord,
+        CTRL_U => Key::KillToStart,
+        CTRL_K => Key::KillToEnd,
+        CTRL_D => Key::Eof,
+        ESC => read_escape(next),
+        0x20..=0x7e => Key::Char(c),
+        _ => Key::Ignore,
+    }
+}
+
+pub struct Editor {
+    history: VecDeque<String>,
+    max_history: usize,
+}
+
+impl Editor {
+    /// Create an editor remembering at most `max_history` lines.
+    pub fn new(max_history: usize) -> Self {
+        Self {
+            history: VecDeque::with_capacity(max_history),
+            max_history,
+        }
+    }
+
+    /// Append `line` to the history, dropping the oldest entry when full.
+    /// Empty lines and repeats of the latest entry are not recorded.
+    pub fn add_history(&mut self, line: &str) {
+        if self.max_history == 0
+            || line.is_empty()
+            || self.history.back().map(|s| s.as_str()) == Some(line)
+        {
+            return;
+        }
+        if self.history.len() == self.max_history {
+            self.history.pop_front();
+        }
+        self.history.push_back(String::from(line));
+    }
+
+    /// History entries, oldest first.
+    pub fn history(&self) -> impl Iterator<Item = &str> {
+        self.history.iter().map(|s| s.as_str())
+    }
+
+    /// Print `prompt` and edit a line read from stdin. Non-empty lines are added
+    /// to the history. Returns `None` on end of input or Ctrl-D on an empty line.
+    pub fn readline(&mut self, prompt: &str) -> Option<String> {
+        self.readline_with(prompt, read_byte)
+    }
+
+    /// Like `readline`, but takes input bytes from `next` instead of stdin.
+    pub fn readline_with(
+        &mut self,
+        prompt: &str,
+        mut next: impl FnMut() -> Option<u8>,
+    ) -> Option<String> {
+        let mut line = LineState::new(prompt);
+        // index into history while browsing, and the line being edited before that
+        let mut browsing: Option<usize> = None;
+        let mut saved: Vec<u8> = Vec::new();
+        line.refresh();
+        loop {
+            match read_key(&mut next) {
+                Key::Enter => break,
+                Key::Eof => {
+                    if line.buf.is_empty() {
+                        println!("");
+                        return None;
+                    }
+                    break;
+                }
+                Key::Char(c) => line.insert(c),
+                Key::Backspace => {
+                    if line.cursor > 0 {
+                        line.cursor -= 1;
+                        line.buf.remove(line.cursor);
+                    }
+                }
+                Key::Delete => {
+                    if line.cursor < line.buf.len() {
+                        line.buf.remove(line.cursor);
+                    }
+                }
+                Key::Left => line.cursor = line.cursor.saturating_sub(1),
+                Key::Right => line.cursor = (line.cursor + 1).min(line.buf.len()),
+                Key::Home => line.cursor = 0,
+                Key::End => line.cursor = line.buf.len(),
+                Key::DeleteWord => {
+                    let end = line.cursor;
+                    let mut start = end;
+                    while start > 0 && line.buf[start - 1] == b' ' {
+                        start -= 1;
+                    }
+                    while start > 0 && line.buf[start - 1] != b' ' {
+                        start -= 1;
+                    }
+                    line.buf.drain(start..end);
+                    line.cursor = start;
+                }
+                Key::KillToStart => {
+                    line.buf.drain(..line.cursor);
+                    line.cursor = 0;
+                }
+                Key::KillToEnd => line.buf.truncate(line.cursor),
+                Key::Up => {
+                    let idx = match browsing {
+                        None if self.history.is_empty() => continue,
+                        None => {
+                            saved.clone_from(&line.buf);
+                            self.history.len() - 1
+                        }
+                        Some(0) => continue,
+                        Some(i) => i - 1,
+                    };
+                    browsing = Some(idx);
+                    line.set(self.history[idx].as_bytes());
+                }
+                Key::Down => match browsing {
+                    None => continue,
+                    Some(i) if i + 1 < self.history.len() => {
+                        browsing = Some(i + 1);
+                        line.set(self.history[i + 1].as_bytes());
+                    }
+                    Some(_) => {
+                        browsing = None;
+                        line.set(&saved);
+                    }
+                },
+                Key::Ignore => continue,
+            }
+            line.refresh();
+        }
+        println!("");
+        // only printable ASCII is ever inserted, so this cannot fail
+        let line = String::from_utf8(line.buf).unwrap();
+        self.add_history(&line);
+        Some(line)
+    }
+}
+
+struct LineState<'a> {
+    prompt: &'a str,
+    buf: Vec<u8>,
+    cursor: usize,
+}
+
+impl<'a> LineState<'a> {
+    fn new(prompt: &'a str) -> Self {
+        Self {
+            prompt,
+            buf: Vec::new(),
+            cursor: 0,
+        }
+    }
+
+    fn insert(&mut self, c: u8) {
+        self.buf.insert(self.cursor, c);
+        self.cursor += 1;
+    }
+
+    fn set(&mut self, content: &[u8]) {
+        self.buf.clear();
+        self.buf.extend_from_slice(content);
+        self.cursor = self.buf.len();
+    }
+
+    /// Redraw the whole line and put the terminal cursor back in place.
+    fn refresh(&self) {
+        // buf only holds printable ASCII
+        let text = core::str::from_utf8(&self.buf).unwrap();
+        print!("\r{}{}\x1b[K", self.prompt, text);
+        let back = self.buf.len() - self.cursor;
+        if back > 0 {
+            print!("\x1b[{}D", back);
+        }
+        flush();
+    }
+}
@@ -1,16 +1,26 @@
 use crate::SignalAction;
 
-use super::{Stat, TimeVal};
+use super::{MailStatus, MemInfo, PollFd, Stat, TimeSpec, TimeVal};
 
 pub const SYSCALL_OPENAT: usize = 56;
 pub const SYSCALL_CLOSE: usize = 57;
 pub const SYSCALL_READ: usize = 63;
 pub const SYSCALL_WRITE: usize = 64;
+pub const SYSCALL_MKNODAT: usize = 33;
+pub const SYSCALL_MKDIRAT: usize = 34;
 pub const SYSCALL_UNLINKAT: usize = 35;
+pub const SYSCALL_SYMLINKAT: usize = 36;
 pub const SYSCALL_LINKAT: usize = 37;
+pub const SYSCALL_RENAMEAT: usize = 38;
+pub const SYSCALL_TRUNCATE: usize = 45;
+pub const SYSCALL_FTRUNCATE: usize = 46;
+pub const SYSCALL_READLINKAT: usize = 78;
+pub const SYSCALL_FSTATAT: usize = 79;
 pub const SYSCALL_FSTAT: usize = 80;
+pub const SYSCALL_PPOLL: usize = 73;
 pub const SYSCALL_EXIT: usize = 93;
-pub const SYSCALL_SLEEP: usize = 101;
+pub const SYSCALL_NANOSLEEP: usize = 101;
+pub const SYSCALL_CLOCK_GETTIME: usize = 113;
 pub const SYSCALL_YIELD: usize = 124;
 pub const SYSCALL_KILL: usize = 129;
 pub const SYSCALL_SIGACTION: usize = 134;
@@ -23,15 +33,41 @@ pub const SYSCALL_FORK: usize = 220;
 pub const SYSCALL_EXEC: usize = 221;
 pub const SYSCALL_WAITPID: usize = 260;
 pub const SYSCALL_SET_PRIORITY: usize = 140;
+pub const SYSCALL_SHMGET: usize = 194;
+pub const SYSCALL_SHMCTL: usize = 195;
+pub const SYSCALL_SHMAT: usize = 196;
+pub const SYSCALL_SHMDT: usize = 197;
+pub const SYSCALL_SOCKET: usize = 198;
+pub const SYSCALL_BIND: usize = 200;
+pub const SYSCALL_LISTEN: usize = 201;
+pub const SYSCALL_ACCEPT: usize = 202;
+pub const SYSCALL_CONNECT: usize = 203;
+pub const SYSCALL_GETSOCKNAME: usize = 204;
+pub const SYSCALL_GETPEERNAME: usize = 205;
+pub const SYSCALL_SENDTO: usize = 206;
+pub const SYSCALL_RECVFROM: usize = 207;
+pub const SYSCALL_SHUTDOWN: usize = 210;
 pub const SYSCALL_SBRK: usize = 214;
 pub const SYSCALL_MUNMAP: usize = 215;
 pub const SYSCALL_MMAP: usize = 222;
+pub const SYSCALL_MPROTECT: usize = 226;
+pub const SYSCALL_GETRANDOM: usize = 278;
 pub const SYSCALL_SPAWN: usize = 400;
 pub const SYSCALL_MAIL_READ: usize = 401;
 pub const SYSCALL_MAIL_WRITE: usize = 402;
+pub const SYSCALL_MAIL_STATUS: usize = 403;
+/// Returns an fd for the caller's own mailbox that `poll` reports readable
+/// while mail is pending. Specific to these kernels, like the other 4xx.
+pub const SYSCALL_MAILBOX_FD: usize = 404;
 pub const SYSCALL_DUP: usize = 24;
+pub const SYSCALL_FCNTL: usize = 25;
 pub const SYSCALL_PIPE: usize = 59;
 pub const SYSCALL_TRACE: usize = 410;
+pub const SYSCALL_MEMINFO: usize = 411;
+/// `dup3(old_fd, new_fd, flags)`. Linux's number is taken by `SYSCALL_DUP`
+/// here, so like the other 4xx numbers this one is specific to these kernels,
+/// and only those with the extension implement it.
+pub const SYSCALL_DUP3: usize = 412;
 pub const SYSCALL_THREAD_CREATE: usize = 460;
 pub const SYSCALL_WAITTID: usize = 462;
 pub const SYSCALL_MUTEX_CREATE: usize = 463;
@@ -128,14 +164,96 @@ pub fn sys_unlinkat(dirfd: usize, path: &str, flags: usize) -> isize {
     syscall(SYSCALL_UNLINKAT, [dirfd, path.as_ptr() as usize, flags])
 }
 
+pub fn sys_symlinkat(target: &str, new_dirfd: usize, link_path: &str) -> isize {
+    syscall(
+        SYSCALL_SYMLINKAT,
+        [
+            target.as_ptr() as usize,
+            new_dirfd,
+            link_path.as_ptr() as usize,
+        ],
+    )
+}
+
+pub fn sys_readlinkat(dirfd: usize, path: &str, buf: &mut [u8]) -> isize {
+    syscall6(
+        SYSCALL_READLINKAT,
+        [
+            dirfd,
+            path.as_ptr() as usize,
+            buf.as_mut_ptr() as usize,
+            buf.len(),
+            0,
+            0,
+        ],
+    )
+}
+
+pub fn sys_mknodat(dirfd: usize, path: &str, mode: u32, dev: usize) -> isize {
+    syscall6(
+        SYSCALL_MKNODAT,
+        [dirfd, path.as_ptr() as usize, mode as usize, dev, 0, 0],
+    )
+}
+
+pub fn sys_mkdirat(dirfd: usize, path: &str, mode: u32) -> isize {
+    syscall(
+        SYSCALL_MKDIRAT,
+        [dirfd, path.as_ptr() as usize, mode as usize],
+    )
+}
+
+pub fn sys_renameat(old_dirfd: usize, old_path: &str, new_dirfd: usize, new_path: &str) -> isize {
+    syscall6(
+        SYSCALL_RENAMEAT,
+        [
+            old_dirfd,
+            old_path.as_ptr() as usize,
+            new_dirfd,
+            new_path.as_ptr() as usize,
+            0,
+            0,
+        ],
+    )
+}
+
+pub fn sys_truncate(path: &str, len: usize) -> isize {
+    syscall(SYSCALL_TRUNCATE, [path.as_ptr() as usize, len, 0])
+}
+
+pub fn sys_ftruncate(fd: usize, len: usize) -> isize {
+    syscall(SYSCALL_FTRUNCATE, [fd, len, 0])
+}
+
+pub fn sys_fstatat(dirfd: usize, path: &str, st: &mut Stat, flags: usize) -> isize {
+    syscall6(
+        SYSCALL_FSTATAT,
+        [
+            dirfd,
+            path.as_ptr() as usize,
+            st as *mut _ as usize,
+            flags,
+            0,
+            0,
+        ],
+    )
+}
+
 pub fn sys_fstat(fd: usize, st: &mut Stat) -> isize {
     syscall(SYSCALL_FSTAT, [fd, st as *const _ as usize, 0])
 }
 
-pub fn sys_mail_read(buffer: &mut [u8]) -> isize {
-    syscall(
+pub fn sys_mail_read(buffer: &mut [u8], flags: u32, sender: *mut usize) -> isize {
+    syscall6(
         SYSCALL_MAIL_READ,
-        [buffer.as_ptr() as usize, buffer.len(), 0],
+        [
+            buffer.as_ptr() as usize,
+            buffer.len(),
+            flags as usize,
+            sender as usize,
+            0,
+            0,
+        ],
     )
 }
 
@@ -146,13 +264,24 @@ pub fn sys_mail_write(pid: usize, buffer: &[u8]) -> isize {
     )
 }
 
+pub fn sys_mail_status(pid: usize, status: &mut MailStatus) -> isize {
+    syscall(SYSCALL_MAIL_STATUS, [pid, status as *mut _ as usize, 0])
+}
+
+pub fn sys_mailbox_fd() -> isize {
+    syscall(SYSCALL_MAILBOX_FD, [0, 0, 0])
+}
+
 pub fn sys_exit(exit_code: i32) -> ! {
     syscall(SYSCALL_EXIT, [exit_code as usize, 0, 0]);
     panic!("sys_exit never returns!");
 }
 
-pub fn sys_sleep(sleep_ms: usize) -> isize {
-    syscall(SYSCALL_SLEEP, [sleep_ms, 0, 0])
+pub fn sys_nanosleep(req: &TimeSpec, rem: &mut TimeSpec) -> isize {
+    syscall(
+        SYSCALL_NANOSLEEP,
+        [req as *const _ as usize, rem as *mut _ as usize, 0],
+    )
 }
 
 pub fn sys_yield() -> isize {
@@ -163,6 +292,10 @@ pub fn sys_get_time(time: &mut TimeVal, tz: usize) -> isize {
     syscall(SYSCALL_GETTIMEOFDAY, [time as *const _ as usize, tz, 0])
 }
 
+pub fn sys_clock_gettime(clock_id: usize, ts: &mut TimeSpec) -> isize {
+    syscall(SYSCALL_CLOCK_GETTIME, [clock_id, ts as *mut _ as usize, 0])
+}
+
 pub fn sys_getpid() -> isize {
     syscall(SYSCALL_GETPID, [0, 0, 0])
 }
@@ -178,8 +311,11 @@ pub fn sys_exec(path: &str, args: &[*const u8]) -> isize {
     )
 }
 
-pub fn sys_waitpid(pid: isize, xstatus: *mut i32) -> isize {
-    syscall(SYSCALL_WAITPID, [pid as usize, xstatus as usize, 0])
+pub fn sys_waitpid(pid: isize, xstatus: *mut i32, options: u32) -> isize {
+    syscall(
+        SYSCALL_WAITPID,
+        [pid as usize, xstatus as usize, options as usize],
+    )
 }
 
 pub fn sys_set_priority(prio: isize) -> isize {
@@ -190,14 +326,120 @@ pub fn sys_sbrk(size: i32) -> isize {
     syscall(SYSCALL_SBRK, [size as usize, 0, 0])
 }
 
-pub fn sys_mmap(start: usize, len: usize, prot: usize) -> isize {
-    syscall(SYSCALL_MMAP, [start, len, prot])
+pub fn sys_mmap(
+    start: usize,
+    len: usize,
+    prot: usize,
+    flags: usize,
+    fd: usize,
+    offset: usize,
+) -> isize {
+    syscall6(SYSCALL_MMAP, [start, len, prot, flags, fd, offset])
 }
 
 pub fn sys_munmap(start: usize, len: usize) -> isize {
     syscall(SYSCALL_MUNMAP, [start, len, 0])
 }
 
+pub fn sys_mprotect(start: usize, len: usize, prot: usize) -> isize {
+    syscall(SYSCALL_MPROTECT, [start, len, prot])
+}
+
+pub fn sys_shmget(key: usize, size: usize, flags: u32) -> isize {
+    syscall(SYSCALL_SHMGET, [key, size, flags as usize])
+}
+
+pub fn sys_shmctl(id: usize, cmd: usize, buf: usize) -> isize {
+    syscall(SYSCALL_SHMCTL, [id, cmd, buf])
+}
+
+pub fn sys_shmat(id: usize, addr: usize, flags: u32) -> isize {
+    syscall(SYSCALL_SHMAT, [id, addr, flags as usize])
+}
+
+pub fn sys_shmdt(addr: usize) -> isize {
+    syscall(SYSCALL_SHMDT, [addr, 0, 0])
+}
+
+pub fn sys_getrandom(buf: &mut [u8], flags: u32) -> isize {
+    syscall(
+        SYSCALL_GETRANDOM,
+        [buf.as_mut_ptr() as usize, buf.len(), flags as usize],
+    )
+}
+
+pub fn sys_socket(domain: usize, ty: usize, protocol: usize) -> isize {
+    syscall(SYSCALL_SOCKET, [domain, ty, protocol])
+}
+
+pub fn sys_bind(fd: usize, addr: &[u8]) -> isize {
+    syscall(SYSCALL_BIND, [fd, addr.as_ptr() as usize, addr.len()])
+}
+
+pub fn sys_listen(fd: usize, backlog: usize) -> isize {
+    syscall(SYSCALL_LISTEN, [fd, backlog, 0])
+}
+
+pub fn sys_accept(fd: usize, addr: *mut u8, addrlen: *mut u32) -> isize {
+    syscall(SYSCALL_ACCEPT, [fd, addr as usize, addrlen as usize])
+}
+
+pub fn sys_connect(fd: usize, addr: &[u8]) -> isize {
+    syscall(SYSCALL_CONNECT, [fd, addr.as_ptr() as usize, addr.len()])
+}
+
+pub fn sys_getsockname(fd: usize, addr: *mut u8, addrlen: *mut u32) -> isize {
+    syscall(SYSCALL_GETSOCKNAME, [fd, addr as usize, addrlen as usize])
+}
+
+pub fn sys_getpeername(fd: usize, addr: *mut u8, addrlen: *mut u32) -> isize {
+    syscall(SYSCALL_GETPEERNAME, [fd, addr as usize, addrlen as usize])
+}
+
+pub fn sys_sendto(fd: usize, buf: &[u8], flags: u32, addr: &[u8]) -> isize {
+    // an empty addr sends to the connected peer
+    let addr_ptr = if addr.is_empty() {
+        0
+    } else {
+        addr.as_ptr() as usize
+    };
+    syscall6(
+        SYSCALL_SENDTO,
+        [
+            fd,
+            buf.as_ptr() as usize,
+            buf.len(),
+            flags as usize,
+            addr_ptr,
+            addr.len(),
+        ],
+    )
+}
+
+pub fn sys_recvfrom(
+    fd: usize,
+    buf: &mut [u8],
+    flags: u32,
+    addr: *mut u8,
+    addrlen: *mut u32,
+) -> isize {
+    syscall6(
+        SYSCALL_RECVFROM,
+        [
+            fd,
+            buf.as_mut_ptr() as usize,
+            buf.len(),
+            flags as usize,
+            addr as usize,
+            addrlen as usize,
+        ],
+    )
+}
+
+pub fn sys_shutdown(fd: usize, how: usize) -> isize {
+    syscall(SYSCALL_SHUTDOWN, [fd, how, 0])
+}
+
 pub fn sys_spawn(path: &str) -> isize {
     syscall(SYSCALL_SPAWN, [path.as_ptr() as usize, 0, 0])
 }
@@ -206,14 +448,52 @@ pub fn sys_dup(fd: usize) -> isize {
     syscall(SYSCALL_DUP, [fd, 0, 0])
 }
 
+pub fn sys_dup3(old_fd: usize, new_fd: usize, flags: u32) -> isize {
+    syscall(SYSCALL_DUP3, [old_fd, new_fd, flags as usize])
+}
+
 pub fn sys_pipe(pipe: &mut [usize]) -> isize {
     syscall(SYSCALL_PIPE, [pipe.as_mut_ptr() as usize, 0, 0])
 }
 
+pub fn sys_pipe2(pipe: &mut [usize], flags: u32) -> isize {
+    syscall(
+        SYSCALL_PIPE,
+        [pipe.as_mut_ptr() as usize, flags as usize, 0],
+    )
+}
+
+pub fn sys_fcntl(fd: usize, cmd: usize, arg: usize) -> isize {
+    syscall(SYSCALL_FCNTL, [fd, cmd, arg])
+}
+
+pub fn sys_ppoll(
+    fds: &mut [PollFd],
+    timeout: *const TimeSpec,
+    sigmask: *const u64,
+    sigsetsize: usize,
+) -> isize {
+    syscall6(
+        SYSCALL_PPOLL,
+        [
+            fds.as_mut_ptr() as usize,
+            fds.len(),
+            timeout as usize,
+            sigmask as usize,
+            sigsetsize,
+            0,
+        ],
+    )
+}
+
 pub fn sys_trace(trace_request: usize, id: usize, data: usize) -> isize {
     syscall(SYSCALL_TRACE, [trace_request, id, data])
 }
 
+pub fn sys_meminfo(info: &mut MemInfo) -> isize {
+    syscall(SYSCALL_MEMINFO, [info as *mut _ as usize, 0, 0])
+}
+
 pub fn sys_thread_create(entry: usize, arg: usize) -> isize {
     syscall(SYSCALL_THREAD_CREATE, [entry, arg, 0])
 }
@@ -222,8 +502,8 @@ pub fn sys_gettid() -> isize {
     syscall(SYSCALL_GETTID, [0; 3])
 }
 
-pub fn sys_waittid(tid: usize) -> isize {
-    syscall(SYSCALL_WAITTID, [tid, 0, 0])
+pub fn sys_waittid(tid: usize, options: u32) -> isize {
+    syscall(SYSCALL_WAITTID, [tid, options as usize, 0])
 }
 
 pub fn sys_mutex_create(blocking: bool) -> isize {
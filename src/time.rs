@@ -0,0 +1,148 @@
+//! Monotonic and wall-clock time.
+//!
+//! `Instant` reads `CLOCK_MONOTONIC` through `clock_gettime` and `SystemTime`
+//! reads `CLOCK_REALTIME`. Kernels that only implement `gettimeofday` are
+//! still supported, at microsecond resolution. All arithmetic saturates
+//! instead of panicking or wrapping.
+
+use core::ops::{Add, AddAssign, Sub, SubAssign};
+pub use core::time::Duration;
+
+use super::{sys_clock_gettime, sys_get_time, TimeSpec, TimeVal};
+
+pub const CLOCK_REALTIME: usize = 0;
+pub const CLOCK_MONOTONIC: usize = 1;
+
+fn clock_now(clock_id: usize) -> Duration {
+    let mut ts = TimeSpec::new();
+    if sys_clock_gettime(clock_id, &mut ts) == 0 {
+        return ts.into();
+    }
+    let mut tv = TimeVal::new();
+    match sys_get_time(&mut tv, 0) {
+        0 => Duration::new(tv.sec as u64, (tv.usec * 1000) as u32),
+        _ => Duration::ZERO,
+    }
+}
+
+/// A point on the monotonic clock, only meaningful relative to other `Instant`s.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Instant(Duration);
+
+impl Instant {
+    pub fn now() -> Self {
+        Instant(clock_now(CLOCK_MONOTONIC))
+    }
+
+    /// Time elapsed from `earlier` to `self`, or zero if `earlier` is later.
+    pub fn duration_since(&self, earlier: Instant) -> Duration {
+        self.0.saturating_sub(earlier.0)
+    }
+
+    pub fn checked_duration_since(&self, earlier: Instant) -> Option<Duration> {
+        self.0.checked_sub(earlier.0)
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        Instant::now().duration_since(*self)
+    }
+
+    pub fn checked_add(&self, duration: Duration) -> Option<Instant> {
+        self.0.checked_add(duration).map(Instant)
+    }
+
+    pub fn checked_sub(&self, duration: Duration) -> Option<Instant> {
+        self.0.checked_sub(duration).map(Instant)
+    }
+
+    /// Time since the clock's origin (usually boot).
+    pub fn as_duration(&self) -> Duration {
+        self.0
+    }
+}
+
+impl Add<Duration> for Instant {
+    type Output = Instant;
+
+    fn add(self, rhs: Duration) -> Instant {
+        Instant(self.0.saturating_add(rhs))
+    }
+}
+
+impl AddAssign<Duration> for Instant {
+    fn add_assign(&mut self, rhs: Duration) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub<Duration> for Instant {
+    type Output = Instant;
+
+    fn sub(self, rhs: Duration) -> Instant {
+        Instant(self.0.saturating_sub(rhs))
+    }
+}
+
+impl SubAssign<Duration> for Instant {
+    fn sub_assign(&mut self, rhs: Duration) {
+        *self = *self - rhs;
+    }
+}
+
+impl Sub<Instant> for Instant {
+    type Output = Duration;
+
+    fn sub(self, rhs: Instant) -> Duration {
+        self.duration_since(rhs)
+    }
+}
+
+/// A wall-clock timestamp. It may jump backwards, use `Instant` for measurements.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SystemTime(Duration);
+
+pub const UNIX_EPOCH: SystemTime = SystemTime(Duration::ZERO);
+
+impl SystemTime {
+    pub const UNIX_EPOCH: SystemTime = UNIX_EPOCH;
+
+    pub fn now() -> Self {
+        SystemTime(clock_now(CLOCK_REALTIME))
+    }
+
+    /// `Err` holds how far `self` lies before `earlier`.
+    pub fn duration_since(&self, earlier: SystemTime) -> Result<Duration, Duration> {
+        match self.0.checked_sub(earlier.0) {
+            Some(d) => Ok(d),
+            None => Err(earlier.0 - self.0),
+        }
+    }
+
+    pub fn elapsed(&self) -> Result<Duration, Duration> {
+        SystemTime::now().duration_since(*self)
+    }
+
+    pub fn checked_add(&self, duration: Duration) -> Option<SystemTime> {
+        self.0.checked_add(duration).map(SystemTime)
+    }
+
+    pub fn checked_sub(&self, duration: Duration) -> Option<SystemTime> {
+        self.0.checked_sub(duration).map(SystemTime)
+    }
+}
+
+impl Add<Duration> for SystemTime {
+    type Output = SystemTime;
+
+    fn add(self, rhs: Duration) -> SystemTime {
+        SystemTime(self.0.saturating_add(rhs))
+    }
+}
+
+impl Sub<Duration> for SystemTime {
+    type Output = SystemTime;
+
+    fn sub(self, rhs: Duration) -> SystemTime {
+        SystemTime(self.0.saturating_sub(rhs))
+    }
+}
@@ -0,0 +1,78 @@
+import os
+import shlex
+import struct
+import subprocess
+import sys
+import tempfile
+
+# Embed a compact symbol table into the .usersym section of a user ELF so that
+# panic backtraces can be symbolised, see src/backtrace.rs for the layout.
+
+section = ".usersym"
+size = 8192
+max_name = 80
+nm = os.getenv("NM", "rust-nm")
+objcopy = os.getenv("OBJCOPY", "rust-objcopy")
+
+escapes = {
+    "$SP$": "@", "$BP$": "*", "$RF$": "&", "$LT$": "<", "$GT$": ">",
+    "$LP$": "(", "$RP$": ")", "$C$": ",", "$u20$": " ", "$u27$": "'",
+    "$u5b$": "[", "$u5d$": "]", "$u7b$": "{", "$u7d$": "}", "$u7e$": "~",
+}
+
+def unescape(name):
+    # legacy rust mangling leaves these escapes in demangled path segments
+    if name.startswith("_$"):
+        name = name[1:]
+    name = name.replace("::_$", "::$")
+    for k, v in escapes.items():
+        name = name.replace(k, v)
+    return name.replace("..", "::")
+
+def symbols(elf):
+    out = subprocess.run(
+        shlex.split(nm) + ["--defined-only", "--numeric-sort", "--demangle", elf],
+        capture_output=True, text=True, check=True,
+    ).stdout
+    syms = []
+    for line in out.splitlines():
+        parts = line.split(" ", 2)
+        if len(parts) != 3 or parts[1] not in "tTwW" or parts[2].startswith((".L", "$")):
+            continue
+        addr, name = int(parts[0], 16), parts[2]
+        # drop the ::h0123456789abcdef hash suffix of rust symbols
+        if len(name) > 19 and name[-19:-16] == "::h":
+            name = name[:-19]
+        # several symbols may share an address, e.g. stext and _start
+        if syms and syms[-1][0] == addr:
+            continue
+        syms.append((addr, unescape(name)[:max_name].encode()))
+    return syms
+
+def pack(syms):
+    while True:
+        header = 8 + 16 * len(syms)
+        names = b"".join(name for _, name in syms)
+        if header + len(names) <= size:
+            break
+        # keep the lowest addresses, which include everything before the lib
+        syms = syms[: len(syms) * 9 // 10]
+        print("[symtab.py] table full, keeping %d symbols" % len(syms))
+    data = b"USYM" + struct.pack("<I", len(syms))
+    offset = header
+    for addr, name in syms:
+        data += struct.pack("<QII", addr, offset, len(name))
+        offset += len(name)
+    data += names
+    return data + bytes(size - len(data))
+
+for elf in sys.argv[1:]:
+    try:
+        data = pack(symbols(elf))
+    except (OSError, subprocess.CalledProcessError) as e:
+        print("[symtab.py] skip %s: %s" % (elf, e))
+        continue
+    with tempfile.NamedTemporaryFile(delete=False) as f:
+        f.write(data)
+    os.system("%s --update-section %s=%s %s" % (objcopy, section, f.name, elf))
+    os.unlink(f.name)